@@ -1,13 +1,11 @@
 extern crate wave_test_lib;
 
 fn main() {
-    let checker = wave_test_lib::Checker::new();
-    let std_ans = checker.std_answer();
-    let usr_ans = checker.user_answer();
+    let mut checker = wave_test_lib::Checker::new();
     
-    while let Some(expected) = std_ans.read_token_as::<f64>() {
-        usr_ans.expect_float_eq(expected, 1e-8);
+    while let Some(expected) = checker.std_answer().read_token_as::<f64>() {
+        checker.user_answer().expect_float_eq(expected, 1e-8);
     }
 
-    usr_ans.expect_eof();
+    checker.user_answer().expect_eof();
 }
@@ -26,6 +26,15 @@ typedef int32_t wave_ordering;
 #define WAVE_GREATER        (1)
 #define WAVE_NOT_COMPARABLE (2147483647)
 
+typedef int32_t wave_verdict;
+
+#define WAVE_VERDICT_ACCEPTED           (0)
+#define WAVE_VERDICT_WRONG_ANSWER       (1)
+#define WAVE_VERDICT_PRESENTATION_ERROR (2)
+#define WAVE_VERDICT_PARTIAL_SCORE      (3)
+#define WAVE_VERDICT_JUDGE_FAILURE      (4)
+#define WAVE_VERDICT_INVALID_INPUT      (5)
+
 
 void 
 wave_accept();
@@ -40,6 +49,45 @@ wave_reject(
   const char* msg
 );
 
+void
+wave_presentation_error(
+  const char* msg
+);
+
+void
+wave_partial(
+  double score,
+  const char* msg
+);
+
+void
+wave_partial_points(
+  double points,
+  double max_points,
+  const char* msg
+);
+
+void
+wave_judge_fail(
+  const char* msg
+);
+
+void
+wave_feedback(
+  const char* msg
+);
+
+void
+wave_judge_log(
+  const char* msg
+);
+
+void
+wave_quit(
+  wave_verdict verdict,
+  const char* msg
+);
+
 wave_handle
 wave_checker_create();
 
@@ -91,6 +139,52 @@ wave_interactor_get_write_end_handle(
   wave_handle interactor
 );
 
+wave_handle
+wave_validator_create();
+
+void
+wave_validator_release(
+  wave_handle validator
+);
+
+int64_t
+wave_validator_read_i64_in_range(
+  wave_handle validator,
+  int64_t lo,
+  int64_t hi,
+  const char* name
+);
+
+uint64_t
+wave_validator_read_u64_in_range(
+  wave_handle validator,
+  uint64_t lo,
+  uint64_t hi,
+  const char* name
+);
+
+size_t
+wave_validator_read_token(
+  wave_handle validator,
+  void* buffer,
+  size_t buffer_size
+);
+
+void
+wave_validator_expect_space(
+  wave_handle validator
+);
+
+void
+wave_validator_expect_eoln(
+  wave_handle validator
+);
+
+void
+wave_validator_finish(
+  wave_handle validator
+);
+
 size_t
 wave_read_token(
   wave_handle handle,
@@ -105,6 +199,16 @@ wave_read_line(
   size_t buffer_size
 );
 
+// Set the syntax accepted by the wave_expect_i* and wave_expect_u* functions
+// on the reader. By default, a leading plus sign is rejected and leading zeros
+// are accepted.
+void
+wave_set_number_format(
+  wave_handle handle,
+  wave_bool allow_plus_sign,
+  wave_bool allow_leading_zeros
+);
+
 int8_t
 wave_expect_i8(
   wave_handle handle
@@ -171,6 +275,117 @@ wave_expect_fp(
   double tolerance
 );
 
+void
+wave_expect_decimal(
+  wave_handle handle,
+  const char* expected,
+  uint32_t precision,
+  int32_t strict
+);
+
+void
+wave_expect_bigint(
+  wave_handle handle,
+  const char* expected
+);
+
+void
+wave_expect_fp_rel(
+  wave_handle handle,
+  double expected,
+  double tolerance
+);
+
+void
+wave_expect_fp_abs_or_rel(
+  wave_handle handle,
+  double expected,
+  double abs_tolerance,
+  double rel_tolerance
+);
+
+void
+wave_expect_fp_ulp(
+  wave_handle handle,
+  double expected,
+  uint64_t max_ulps
+);
+
+int64_t
+wave_expect_i64_range(
+  wave_handle handle,
+  int64_t lo,
+  int64_t hi,
+  const char* name
+);
+
+double
+wave_expect_fp_range(
+  wave_handle handle,
+  double lo,
+  double hi,
+  const char* name
+);
+
+void
+wave_expect_i32_vec(
+  wave_handle handle,
+  int32_t* buffer,
+  size_t n,
+  const char* name
+);
+
+void
+wave_expect_i64_vec(
+  wave_handle handle,
+  int64_t* buffer,
+  size_t n,
+  const char* name
+);
+
+void
+wave_expect_u64_vec(
+  wave_handle handle,
+  uint64_t* buffer,
+  size_t n,
+  const char* name
+);
+
+void
+wave_expect_fp_vec(
+  wave_handle handle,
+  double* buffer,
+  size_t n,
+  const char* name
+);
+
+void
+wave_expect_i64_vec_range(
+  wave_handle handle,
+  int64_t* buffer,
+  size_t n,
+  int64_t lo,
+  int64_t hi,
+  const char* name
+);
+
+void
+wave_expect_permutation(
+  wave_handle handle,
+  size_t* buffer,
+  size_t n,
+  const char* name
+);
+
+void
+wave_expect_i64_matrix(
+  wave_handle handle,
+  int64_t* buffer,
+  size_t rows,
+  size_t cols,
+  const char* name
+);
+
 void
 wave_expect_eof(
   wave_handle handle
@@ -183,6 +398,36 @@ wave_cmp_fp(
   double tolerance
 );
 
+wave_partial_ordering
+wave_cmp_decimal(
+  const char* actual,
+  const char* expected,
+  uint32_t precision,
+  int32_t strict
+);
+
+wave_partial_ordering
+wave_cmp_fp_rel(
+  double actual,
+  double expected,
+  double tolerance
+);
+
+wave_partial_ordering
+wave_cmp_fp_abs_or_rel(
+  double actual,
+  double expected,
+  double abs_tolerance,
+  double rel_tolerance
+);
+
+wave_partial_ordering
+wave_cmp_fp_ulp(
+  double actual,
+  double expected,
+  uint64_t max_ulps
+);
+
 wave_ordering
 wave_cmp_str(
   const char* actual,
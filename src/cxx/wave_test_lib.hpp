@@ -49,6 +49,89 @@ inline static void reject(const std::string& msg) {
   ::wave_reject(msg.c_str());
 }
 
+WAVE_ATTR_NO_RETURN
+inline static void presentation_error(const char* msg) {
+  ::wave_presentation_error(msg);
+}
+
+WAVE_ATTR_NO_RETURN
+inline static void presentation_error(const std::string& msg) {
+  ::wave_presentation_error(msg.c_str());
+}
+
+WAVE_ATTR_NO_RETURN
+inline static void partial(double score) {
+  ::wave_partial(score, nullptr);
+}
+
+WAVE_ATTR_NO_RETURN
+inline static void partial(double score, const char* msg) {
+  ::wave_partial(score, msg);
+}
+
+WAVE_ATTR_NO_RETURN
+inline static void partial(double score, const std::string& msg) {
+  ::wave_partial(score, msg.c_str());
+}
+
+WAVE_ATTR_NO_RETURN
+inline static void partial_points(double points, double max_points) {
+  ::wave_partial_points(points, max_points, nullptr);
+}
+
+WAVE_ATTR_NO_RETURN
+inline static void partial_points(double points, double max_points, 
+                                  const char* msg) {
+  ::wave_partial_points(points, max_points, msg);
+}
+
+WAVE_ATTR_NO_RETURN
+inline static void partial_points(double points, double max_points, 
+                                  const std::string& msg) {
+  ::wave_partial_points(points, max_points, msg.c_str());
+}
+
+WAVE_ATTR_NO_RETURN
+inline static void judge_fail(const char* msg) {
+  ::wave_judge_fail(msg);
+}
+
+WAVE_ATTR_NO_RETURN
+inline static void judge_fail(const std::string& msg) {
+  ::wave_judge_fail(msg.c_str());
+}
+
+inline static void feedback(const char* msg) {
+  ::wave_feedback(msg);
+}
+
+inline static void feedback(const std::string& msg) {
+  ::wave_feedback(msg.c_str());
+}
+
+inline static void judge_log(const char* msg) {
+  ::wave_judge_log(msg);
+}
+
+inline static void judge_log(const std::string& msg) {
+  ::wave_judge_log(msg.c_str());
+}
+
+WAVE_ATTR_NO_RETURN
+inline static void quit(::wave_verdict verdict) {
+  ::wave_quit(verdict, nullptr);
+}
+
+WAVE_ATTR_NO_RETURN
+inline static void quit(::wave_verdict verdict, const char* msg) {
+  ::wave_quit(verdict, msg);
+}
+
+WAVE_ATTR_NO_RETURN
+inline static void quit(::wave_verdict verdict, const std::string& msg) {
+  ::wave_quit(verdict, msg.c_str());
+}
+
 
 namespace i {
 
@@ -69,7 +152,7 @@ private:
 };
 
 template <typename D>
-class HandleOwner : HandleWrapper {
+class HandleOwner : protected HandleWrapper {
 protected:
   explicit HandleOwner(::wave_handle handle, D deleter)
     : HandleWrapper { handle }, 
@@ -98,6 +181,12 @@ struct InteractorHandleDeleter {
   }
 };
 
+struct ValidatorHandleDeleter {
+  void operator()(::wave_handle handle) const {
+    ::wave_validator_release(handle);
+  }
+};
+
 } // namespace i
 
 
@@ -132,6 +221,45 @@ public:
 };
 
 
+class Validator : private i::HandleOwner<i::ValidatorHandleDeleter> {
+public:
+  explicit Validator()
+    : HandleOwner { ::wave_validator_create(), i::ValidatorHandleDeleter { } }
+  { }
+
+  DELETE_COPY_MOVE(Validator)
+
+  int64_t read_i64_in_range(int64_t lo, int64_t hi, const char* name) {
+    return ::wave_validator_read_i64_in_range(handle(), lo, hi, name);
+  }
+
+  uint64_t read_u64_in_range(uint64_t lo, uint64_t hi, const char* name) {
+    return ::wave_validator_read_u64_in_range(handle(), lo, hi, name);
+  }
+
+  std::string read_token() {
+    auto size = ::wave_validator_read_token(handle(), nullptr, 0);
+    std::string token(size, '\0');
+    ::wave_validator_read_token(handle(), &token[0], size);
+    token.pop_back();
+    return token;
+  }
+
+  void expect_space() {
+    ::wave_validator_expect_space(handle());
+  }
+
+  void expect_eoln() {
+    ::wave_validator_expect_eoln(handle());
+  }
+
+  WAVE_ATTR_NO_RETURN
+  void finish() {
+    ::wave_validator_finish(handle());
+  }
+};
+
+
 } // namespace wave
 
 
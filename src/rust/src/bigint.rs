@@ -0,0 +1,429 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+
+
+/// Base of the limbs of a [`BigInt`].
+const BASE: u64 = 1_000_000_000;
+
+/// Number of decimal digits in one limb of a [`BigInt`].
+const BASE_DIGITS: usize = 9;
+
+/// Error raised when parsing a [`BigInt`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseBigIntError {
+    /// The text contains no digits.
+    Empty,
+
+    /// The text contains a character that is not a decimal digit.
+    InvalidDigit,
+
+    /// The text starts with a `+` sign, which is not canonical.
+    PlusSign,
+
+    /// The text contains leading zeros, which is not canonical.
+    LeadingZero,
+
+    /// The text is `-0`, which is not canonical.
+    NegativeZero
+}
+
+impl Display for ParseBigIntError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            ParseBigIntError::Empty => "integer has no digits",
+            ParseBigIntError::InvalidDigit => "invalid digit in integer",
+            ParseBigIntError::PlusSign => "leading plus sign is not allowed",
+            ParseBigIntError::LeadingZero => "leading zeros are not allowed",
+            ParseBigIntError::NegativeZero => "negative zero is not allowed"
+        })
+    }
+}
+
+impl std::error::Error for ParseBigIntError { }
+
+/// An arbitrary-precision signed integer.
+///
+/// Parsing a [`BigInt`] through [`FromStr`] accepts the canonical form only, i.e.
+/// no `+` sign, no leading zeros and no `-0`, so that reading a [`BigInt`] token
+/// with [`crate::contract::JudgeReader::expect_eq`] also validates its format.
+/// Use [`BigInt::parse_lenient`] to read integers whose format does not matter,
+/// such as those in judge files.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct BigInt {
+    negative: bool,
+
+    /// Limbs in base `10^9`, least significant first, without leading zero
+    /// limbs. Empty if the number is zero.
+    limbs: Vec<u32>
+}
+
+impl BigInt {
+    /// Create a new [`BigInt`] instance from its sign and limbs, normalizing away
+    /// leading zero limbs and the sign of zero.
+    fn from_limbs(negative: bool, mut limbs: Vec<u32>) -> BigInt {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        let negative = negative && !limbs.is_empty();
+        BigInt { negative, limbs }
+    }
+
+    /// Parse the given decimal digits, which must be non-empty.
+    fn from_digits(negative: bool, digits: &[u8]) -> BigInt {
+        let mut limbs = Vec::with_capacity(digits.len() / BASE_DIGITS + 1);
+        let mut end = digits.len();
+        while end > 0 {
+            let start = end.saturating_sub(BASE_DIGITS);
+            let limb = digits[start..end].iter()
+                .fold(0u32, |limb, d| limb * 10 + u32::from(d - b'0'));
+            limbs.push(limb);
+            end = start;
+        }
+        BigInt::from_limbs(negative, limbs)
+    }
+
+    /// Parse the given text into a [`BigInt`] instance, accepting a leading `+`
+    /// sign, leading zeros and `-0`.
+    pub fn parse_lenient(text: &str) -> Result<BigInt, ParseBigIntError> {
+        let (negative, digits) = match text.as_bytes().first() {
+            Some(b'+') => (false, &text.as_bytes()[1..]),
+            Some(b'-') => (true, &text.as_bytes()[1..]),
+            _ => (false, text.as_bytes())
+        };
+        if digits.is_empty() {
+            return Err(ParseBigIntError::Empty);
+        }
+        if !digits.iter().all(u8::is_ascii_digit) {
+            return Err(ParseBigIntError::InvalidDigit);
+        }
+        Ok(BigInt::from_digits(negative, digits))
+    }
+
+    /// Determine whether this number is zero.
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Determine whether this number is less than zero.
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Get the absolute value of this number.
+    pub fn abs(&self) -> BigInt {
+        BigInt { negative: false, limbs: self.limbs.clone() }
+    }
+
+    /// Divide this number by `divisor`, rounding toward zero, and get the
+    /// quotient and the remainder. The remainder has the same sign as this
+    /// number. Panics if `divisor` is zero.
+    pub fn div_rem_u32(&self, divisor: u32) -> (BigInt, i64) {
+        assert!(divisor != 0, "division by zero");
+        let mut quotient = vec![0; self.limbs.len()];
+        let mut remainder = 0u64;
+        for (i, &limb) in self.limbs.iter().enumerate().rev() {
+            let value = remainder * BASE + u64::from(limb);
+            quotient[i] = (value / u64::from(divisor)) as u32;
+            remainder = value % u64::from(divisor);
+        }
+        let remainder = if self.negative { -(remainder as i64) } else { remainder as i64 };
+        (BigInt::from_limbs(self.negative, quotient), remainder)
+    }
+}
+
+/// Compare two magnitudes without leading zero limbs.
+fn compare_magnitudes(lhs: &[u32], rhs: &[u32]) -> Ordering {
+    lhs.len().cmp(&rhs.len())
+        .then_with(|| lhs.iter().rev().cmp(rhs.iter().rev()))
+}
+
+fn add_magnitudes(lhs: &[u32], rhs: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(lhs.len().max(rhs.len()) + 1);
+    let mut carry = 0u64;
+    for i in 0..lhs.len().max(rhs.len()) {
+        let value = u64::from(*lhs.get(i).unwrap_or(&0)) + u64::from(*rhs.get(i).unwrap_or(&0))
+            + carry;
+        result.push((value % BASE) as u32);
+        carry = value / BASE;
+    }
+    if carry > 0 {
+        result.push(carry as u32);
+    }
+    result
+}
+
+/// Subtract `rhs` from `lhs`, which must not be less than `rhs`.
+fn sub_magnitudes(lhs: &[u32], rhs: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(lhs.len());
+    let mut borrow = 0i64;
+    for (i, &limb) in lhs.iter().enumerate() {
+        let mut value = i64::from(limb) - i64::from(*rhs.get(i).unwrap_or(&0)) - borrow;
+        borrow = 0;
+        if value < 0 {
+            value += BASE as i64;
+            borrow = 1;
+        }
+        result.push(value as u32);
+    }
+    result
+}
+
+fn mul_magnitudes(lhs: &[u32], rhs: &[u32]) -> Vec<u32> {
+    if lhs.is_empty() || rhs.is_empty() {
+        return Vec::new();
+    }
+    let mut result = vec![0u64; lhs.len() + rhs.len()];
+    for (i, &l) in lhs.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &r) in rhs.iter().enumerate() {
+            let value = result[i + j] + u64::from(l) * u64::from(r) + carry;
+            result[i + j] = value % BASE;
+            carry = value / BASE;
+        }
+        result[i + rhs.len()] += carry;
+    }
+    result.into_iter().map(|limb| limb as u32).collect()
+}
+
+/// Add two signed numbers given as signs and magnitudes.
+fn add_signed(lhs_negative: bool, lhs: &[u32], rhs_negative: bool, rhs: &[u32]) -> BigInt {
+    if lhs_negative == rhs_negative {
+        return BigInt::from_limbs(lhs_negative, add_magnitudes(lhs, rhs));
+    }
+    match compare_magnitudes(lhs, rhs) {
+        Ordering::Less => BigInt::from_limbs(rhs_negative, sub_magnitudes(rhs, lhs)),
+        _ => BigInt::from_limbs(lhs_negative, sub_magnitudes(lhs, rhs))
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    /// Parse the given text into a [`BigInt`] instance, accepting the canonical
+    /// form only.
+    fn from_str(s: &str) -> Result<BigInt, ParseBigIntError> {
+        let digits = s.strip_prefix('-').unwrap_or(s).as_bytes();
+        if s.starts_with('+') {
+            return Err(ParseBigIntError::PlusSign);
+        }
+        let value = BigInt::parse_lenient(s)?;
+        if digits.len() > 1 && digits[0] == b'0' {
+            return Err(ParseBigIntError::LeadingZero);
+        }
+        if s.starts_with('-') && value.is_zero() {
+            return Err(ParseBigIntError::NegativeZero);
+        }
+        Ok(value)
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let mut limbs = self.limbs.iter().rev();
+        match limbs.next() {
+            Some(most_significant) => {
+                if self.negative {
+                    f.write_str("-")?;
+                }
+                write!(f, "{}", most_significant)?;
+                for limb in limbs {
+                    write!(f, "{:09}", limb)?;
+                }
+                Ok(())
+            },
+            None => f.write_str("0")
+        }
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(value: i64) -> BigInt {
+        let mut magnitude = value.unsigned_abs();
+        let mut limbs = Vec::new();
+        while magnitude > 0 {
+            limbs.push((magnitude % BASE) as u32);
+            magnitude /= BASE;
+        }
+        BigInt::from_limbs(value < 0, limbs)
+    }
+}
+
+impl From<u64> for BigInt {
+    fn from(value: u64) -> BigInt {
+        let mut magnitude = value;
+        let mut limbs = Vec::new();
+        while magnitude > 0 {
+            limbs.push((magnitude % BASE) as u32);
+            magnitude /= BASE;
+        }
+        BigInt::from_limbs(false, limbs)
+    }
+}
+
+impl PartialEq<i64> for BigInt {
+    fn eq(&self, other: &i64) -> bool {
+        // An `i64` fits in three limbs.
+        self.negative == (*other < 0) && self.limbs.len() <= 3 &&
+            self.limbs.iter().rev()
+                .fold(0u128, |value, &limb| value * u128::from(BASE) + u128::from(limb))
+                == u128::from(other.unsigned_abs())
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => compare_magnitudes(&self.limbs, &other.limbs),
+            (true, true) => compare_magnitudes(&other.limbs, &self.limbs)
+        }
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_limbs(!self.negative, self.limbs)
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        -self.clone()
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, rhs: &BigInt) -> BigInt {
+        add_signed(self.negative, &self.limbs, rhs.negative, &rhs.limbs)
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, rhs: &BigInt) -> BigInt {
+        add_signed(self.negative, &self.limbs, !rhs.negative, &rhs.limbs)
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, rhs: &BigInt) -> BigInt {
+        BigInt::from_limbs(self.negative != rhs.negative, mul_magnitudes(&self.limbs, &rhs.limbs))
+    }
+}
+
+impl Add for BigInt {
+    type Output = BigInt;
+
+    fn add(self, rhs: BigInt) -> BigInt {
+        &self + &rhs
+    }
+}
+
+impl Sub for BigInt {
+    type Output = BigInt;
+
+    fn sub(self, rhs: BigInt) -> BigInt {
+        &self - &rhs
+    }
+}
+
+impl Mul for BigInt {
+    type Output = BigInt;
+
+    fn mul(self, rhs: BigInt) -> BigInt {
+        &self * &rhs
+    }
+}
+
+pub mod preclude {
+    pub use super::{BigInt, ParseBigIntError};
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::contract::JudgeReader;
+    use crate::tokenized::TokenizedReader;
+
+    use super::{BigInt, ParseBigIntError};
+
+    fn big(text: &str) -> BigInt {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_canonical() {
+        assert_eq!(big("0").to_string(), "0");
+        assert_eq!(big("-123456789012345678901234567890").to_string(),
+            "-123456789012345678901234567890");
+        assert_eq!(big("1000000000").to_string(), "1000000000");
+
+        assert_eq!("".parse::<BigInt>(), Err(ParseBigIntError::Empty));
+        assert_eq!("-".parse::<BigInt>(), Err(ParseBigIntError::Empty));
+        assert_eq!("12a".parse::<BigInt>(), Err(ParseBigIntError::InvalidDigit));
+        assert_eq!("+1".parse::<BigInt>(), Err(ParseBigIntError::PlusSign));
+        assert_eq!("007".parse::<BigInt>(), Err(ParseBigIntError::LeadingZero));
+        assert_eq!("-00".parse::<BigInt>(), Err(ParseBigIntError::LeadingZero));
+        assert_eq!("-0".parse::<BigInt>(), Err(ParseBigIntError::NegativeZero));
+
+        assert_eq!(BigInt::parse_lenient("+007"), Ok(BigInt::from(7i64)));
+        assert_eq!(BigInt::parse_lenient("-0"), Ok(BigInt::default()));
+    }
+
+    #[test]
+    fn test_compare() {
+        assert!(big("-10") < big("-9"));
+        assert!(big("-1") < big("0"));
+        assert!(big("999999999") < big("1000000000"));
+        assert!(big("123456789123456789123") > big("123456789123456789122"));
+        assert_eq!(big("-42"), -42i64);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = big("999999999999999999");
+        let b = big("1");
+        assert_eq!((&a + &b).to_string(), "1000000000000000000");
+        assert_eq!((&b - &a).to_string(), "-999999999999999998");
+        assert_eq!((&a - &a).to_string(), "0");
+        assert_eq!((&a * &a).to_string(), "999999999999999998000000000000000001");
+        assert_eq!((big("-3") * big("4")).to_string(), "-12");
+        assert_eq!((big("-3") + big("4")).to_string(), "1");
+        assert_eq!((-big("5")).to_string(), "-5");
+        assert_eq!(big("-1000000007").div_rem_u32(10), (big("-100000000"), -7));
+
+        let mut factorial = BigInt::from(1i64);
+        for i in 1..=30i64 {
+            factorial = factorial * BigInt::from(i);
+        }
+        assert_eq!(factorial.to_string(), "265252859812191058636308480000000");
+    }
+
+    #[test]
+    fn test_read_big_int() {
+        let mut reader = JudgeReader::new(TokenizedReader::new(
+            "265252859812191058636308480000000 0265 -0".as_bytes()));
+        let expected = big("265252859812191058636308480000000");
+        assert_eq!(reader.try_expect_eq::<BigInt, BigInt>(&expected), Ok(expected.clone()));
+        assert!(reader.try_expect_eq::<BigInt, BigInt>(&big("265")).is_err());
+        assert!(reader.try_expect_type::<BigInt>().is_err());
+    }
+}
@@ -1,4 +1,6 @@
 use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 
 /// Compare two `f64` values, with an absolute tolerance.
@@ -14,22 +16,96 @@ pub fn compare_floats<T1, T2, T3>(lhs: T1, rhs: T2, tolerance: T3)
     if tolerance.is_nan() {
         panic!("tolerance cannot be NaN.");
     }
-    let tolerance = tolerance.abs();
+    compare_within(lhs, rhs, tolerance.abs())
+}
+
+/// Compare two `f64` values, with a relative tolerance. `rhs` is the reference
+/// value, and the two values are considered equal if
+/// `|lhs - rhs| <= tolerance * |rhs|`.
+pub fn compare_floats_rel<T1, T2, T3>(lhs: T1, rhs: T2, tolerance: T3)
+    -> Option<Ordering>
+    where T1: Into<f64>,
+          T2: Into<f64>,
+          T3: Into<f64> {
+    compare_floats_abs_or_rel(lhs, rhs, 0f64, tolerance)
+}
+
+/// Compare two `f64` values, considering them equal if either the absolute
+/// error or the relative error is within the corresponding tolerance, i.e.
+/// `|lhs - rhs| <= max(abs_tolerance, rel_tolerance * |rhs|)`. `rhs` is the
+/// reference value.
+pub fn compare_floats_abs_or_rel<T1, T2, T3, T4>(lhs: T1, rhs: T2, abs_tolerance: T3, 
+    rel_tolerance: T4) -> Option<Ordering>
+    where T1: Into<f64>,
+          T2: Into<f64>,
+          T3: Into<f64>,
+          T4: Into<f64> {
+    let lhs: f64 = lhs.into();
+    let rhs: f64 = rhs.into();
+    let abs_tolerance: f64 = abs_tolerance.into();
+    let rel_tolerance: f64 = rel_tolerance.into();
+
+    if abs_tolerance.is_nan() || rel_tolerance.is_nan() {
+        panic!("tolerance cannot be NaN.");
+    }
+    let bound = abs_tolerance.abs().max(rel_tolerance.abs() * rhs.abs());
+    compare_within(lhs, rhs, bound)
+}
+
+/// Compare two `f64` values, considering them equal if they are at most
+/// `max_ulps` representable `f64` values apart. `0.0` and `-0.0` are equal. An
+/// infinity is equal to the infinity of the same sign only, however close
+/// `f64::MAX` is to it.
+pub fn compare_floats_ulp(lhs: f64, rhs: f64, max_ulps: u64) -> Option<Ordering> {
+    if lhs.is_nan() || rhs.is_nan() {
+        return None;
+    }
+    if lhs.is_infinite() || rhs.is_infinite() {
+        return lhs.partial_cmp(&rhs);
+    }
+
+    // Map the bit patterns to integers that are ordered the same as the floats,
+    // so that adjacent floats map to adjacent integers.
+    fn ordered_bits(x: f64) -> i128 {
+        let bits = x.to_bits() as i64;
+        if bits < 0 {
+            i128::from(i64::MIN) - i128::from(bits)
+        } else {
+            i128::from(bits)
+        }
+    }
+
+    let diff = ordered_bits(lhs) - ordered_bits(rhs);
+    if diff.unsigned_abs() <= u128::from(max_ulps) {
+        Some(Ordering::Equal)
+    } else if diff < 0 {
+        Some(Ordering::Less)
+    } else {
+        Some(Ordering::Greater)
+    }
+}
 
+/// Compare two `f64` values, considering them equal if they differ by at most
+/// `bound`, which must be non-negative.
+fn compare_within(lhs: f64, rhs: f64, bound: f64) -> Option<Ordering> {
     // If both numbers are `NaN`, the result should be `None`.
     if lhs.is_nan() || rhs.is_nan() {
         return None;
     }
 
     // If both numbers are infinite and the same sign, the result should be
-    // `Ordering::Equal`.
-    if lhs.is_infinite() && rhs.is_infinite() && 
-        lhs.is_sign_positive() == rhs.is_sign_positive() {
-        return Some(Ordering::Equal);
+    // `Ordering::Equal`. An infinite number never equals a finite one, no matter
+    // how large the bound is.
+    if lhs.is_infinite() || rhs.is_infinite() {
+        return if lhs == rhs {
+            Some(Ordering::Equal)
+        } else {
+            lhs.partial_cmp(&rhs)
+        };
     }
 
     let diff = lhs - rhs;   // `diff` cannot be `NaN`.
-    if diff.abs() <= tolerance {
+    if diff.abs() <= bound {
         return Some(Ordering::Equal);
     }
 
@@ -40,9 +116,416 @@ pub fn compare_floats<T1, T2, T3>(lhs: T1, rhs: T2, tolerance: T3)
     }
 }
 
+/// Maximum absolute value of the decimal exponent accepted by [`Decimal::parse`].
+const MAX_DECIMAL_EXPONENT: i64 = 100_000;
+
+/// Maximum absolute value of the binary exponent of hexadecimal floating point
+/// numbers accepted by [`Decimal::parse`].
+const MAX_BINARY_EXPONENT: i64 = 4096;
+
+/// Maximum number of digits before or after the decimal point for a [`Decimal`]
+/// to be displayed in plain notation rather than scientific notation.
+const PLAIN_DISPLAY_DIGITS: i64 = 30;
+
+/// Syntax accepted when parsing a [`Decimal`]. The default syntax accepts
+/// everything that [`Decimal::parse`] can handle; use [`DecimalSyntax::strict`]
+/// to accept plain decimal numbers only.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecimalSyntax {
+    /// Whether a leading `+` sign is accepted.
+    pub allow_plus_sign: bool,
+
+    /// Whether `inf`, `infinity` and `nan` are accepted, case-insensitively.
+    pub allow_special: bool,
+
+    /// Whether hexadecimal floating point numbers such as `0x1.8p3` are accepted.
+    pub allow_hex: bool
+}
+
+impl DecimalSyntax {
+    /// Create a new [`DecimalSyntax`] that accepts plain decimal numbers only,
+    /// optionally with a leading `-` sign and an exponent.
+    pub fn strict() -> DecimalSyntax {
+        DecimalSyntax { allow_plus_sign: false, allow_special: false, allow_hex: false }
+    }
+}
+
+impl Default for DecimalSyntax {
+    fn default() -> DecimalSyntax {
+        DecimalSyntax { allow_plus_sign: true, allow_special: true, allow_hex: true }
+    }
+}
+
+/// Error raised when parsing a [`Decimal`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseDecimalError {
+    /// The text is not a number.
+    InvalidSyntax,
+
+    /// The text starts with a `+` sign, which is not allowed by the syntax.
+    PlusSign,
+
+    /// The text is an infinity or NaN, which is not allowed by the syntax.
+    SpecialValue,
+
+    /// The text is a hexadecimal floating point number, which is not allowed by
+    /// the syntax.
+    HexFloat,
+
+    /// The exponent is out of the supported range.
+    ExponentOutOfRange
+}
+
+impl Display for ParseDecimalError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            ParseDecimalError::InvalidSyntax => "invalid decimal number",
+            ParseDecimalError::PlusSign => "leading plus sign is not allowed",
+            ParseDecimalError::SpecialValue => "infinity and NaN are not allowed",
+            ParseDecimalError::HexFloat => "hexadecimal floating point number is not allowed",
+            ParseDecimalError::ExponentOutOfRange => "exponent is out of the supported range"
+        })
+    }
+}
+
+impl std::error::Error for ParseDecimalError { }
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DecimalKind {
+    Finite,
+    Infinity,
+    NaN
+}
+
+/// A decimal number parsed from its textual form without loss of precision. The
+/// value of a finite [`Decimal`] is `digits * 10^exponent`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Decimal {
+    kind: DecimalKind,
+    negative: bool,
+
+    /// Significant digits, most significant first, without leading or trailing
+    /// zeros. Empty if the number is zero.
+    digits: Vec<u8>,
+    exponent: i64
+}
+
+impl Decimal {
+    /// Parse the given text into a [`Decimal`], accepting the given syntax.
+    pub fn parse(text: &str, syntax: &DecimalSyntax) -> Result<Decimal, ParseDecimalError> {
+        let (negative, rest) = match text.as_bytes().first() {
+            Some(b'+') if !syntax.allow_plus_sign => return Err(ParseDecimalError::PlusSign),
+            Some(b'+') => (false, &text[1..]),
+            Some(b'-') => (true, &text[1..]),
+            _ => (false, text)
+        };
+
+        if ["inf", "infinity", "nan"].iter().any(|name| rest.eq_ignore_ascii_case(name)) {
+            if !syntax.allow_special {
+                return Err(ParseDecimalError::SpecialValue);
+            }
+            let kind = if rest.eq_ignore_ascii_case("nan") {
+                DecimalKind::NaN
+            } else {
+                DecimalKind::Infinity
+            };
+            return Ok(Decimal { kind, negative, digits: Vec::new(), exponent: 0 });
+        }
+
+        let (digits, exponent) = if rest.starts_with("0x") || rest.starts_with("0X") {
+            if !syntax.allow_hex {
+                return Err(ParseDecimalError::HexFloat);
+            }
+            parse_hex(&rest.as_bytes()[2..])?
+        } else {
+            parse_decimal(rest.as_bytes())?
+        };
+        Ok(Decimal::finite(negative, digits, exponent))
+    }
+
+    /// Create a finite [`Decimal`] from the given digits, most significant first,
+    /// normalizing away leading and trailing zeros.
+    fn finite(negative: bool, mut digits: Vec<u8>, mut exponent: i64) -> Decimal {
+        let trailing = digits.iter().rev().take_while(|&&d| d == 0).count();
+        digits.truncate(digits.len() - trailing);
+        exponent += trailing as i64;
+
+        let leading = digits.iter().take_while(|&&d| d == 0).count();
+        digits.drain(..leading);
+        if digits.is_empty() {
+            return Decimal { kind: DecimalKind::Finite, negative: false, digits, exponent: 0 };
+        }
+        Decimal { kind: DecimalKind::Finite, negative, digits, exponent }
+    }
+
+    /// Determine whether this number is NaN.
+    pub fn is_nan(&self) -> bool {
+        self.kind == DecimalKind::NaN
+    }
+
+    /// Determine whether this number is positive or negative infinity.
+    pub fn is_infinite(&self) -> bool {
+        self.kind == DecimalKind::Infinity
+    }
+
+    /// Get the digits of the magnitude of this number, least significant first,
+    /// aligned so that the first digit has the given exponent, which must not be
+    /// greater than the exponent of this number.
+    fn aligned_digits(&self, exponent: i64) -> Vec<u8> {
+        if self.digits.is_empty() {
+            return Vec::new();
+        }
+        let mut aligned = vec![0; (self.exponent - exponent) as usize];
+        aligned.extend(self.digits.iter().rev());
+        aligned
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = ParseDecimalError;
+
+    fn from_str(s: &str) -> Result<Decimal, ParseDecimalError> {
+        Decimal::parse(s, &DecimalSyntax::default())
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let sign = if self.negative { "-" } else { "" };
+        match self.kind {
+            DecimalKind::NaN => return f.write_str("nan"),
+            DecimalKind::Infinity => return write!(f, "{}inf", sign),
+            DecimalKind::Finite => ()
+        };
+        if self.digits.is_empty() {
+            return f.write_str("0");
+        }
+
+        f.write_str(sign)?;
+        let digits: String = self.digits.iter().map(|&d| char::from(b'0' + d)).collect();
+        let len = digits.len() as i64;
+        if self.exponent >= 0 && len + self.exponent <= PLAIN_DISPLAY_DIGITS {
+            // Plain integer, e.g. `1500`.
+            f.write_str(&digits)?;
+            for _ in 0..self.exponent {
+                f.write_str("0")?;
+            }
+        } else if self.exponent < 0 && -self.exponent <= PLAIN_DISPLAY_DIGITS {
+            // Plain fraction, e.g. `0.015` or `1.5`.
+            let point = len + self.exponent;
+            if point > 0 {
+                write!(f, "{}.{}", &digits[..point as usize], &digits[point as usize..])?;
+            } else {
+                f.write_str("0.")?;
+                for _ in 0..-point {
+                    f.write_str("0")?;
+                }
+                f.write_str(&digits)?;
+            }
+        } else {
+            // Scientific notation, e.g. `1.5e-100`.
+            write!(f, "{}", &digits[..1])?;
+            if len > 1 {
+                write!(f, ".{}", &digits[1..])?;
+            }
+            write!(f, "e{}", self.exponent + len - 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse an exponent made of an optional sign and decimal digits, rejecting
+/// values whose absolute value exceeds `max`.
+fn parse_exponent(text: &[u8], max: i64) -> Result<i64, ParseDecimalError> {
+    let (negative, digits) = match text.first() {
+        Some(b'+') => (false, &text[1..]),
+        Some(b'-') => (true, &text[1..]),
+        _ => (false, text)
+    };
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+        return Err(ParseDecimalError::InvalidSyntax);
+    }
+
+    let mut value = 0i64;
+    for &d in digits {
+        value = value * 10 + i64::from(d - b'0');
+        if value > max {
+            return Err(ParseDecimalError::ExponentOutOfRange);
+        }
+    }
+    Ok(if negative { -value } else { value })
+}
+
+/// Split the given mantissa at the optional `.` into its integral and fractional
+/// digits, checking that every digit satisfies `is_digit` and at least one
+/// digit is present.
+fn split_mantissa(mantissa: &[u8], is_digit: fn(&u8) -> bool)
+    -> Result<(&[u8], &[u8]), ParseDecimalError> {
+    let (integral, fraction) = match mantissa.iter().position(|&b| b == b'.') {
+        Some(i) => (&mantissa[..i], &mantissa[i + 1..]),
+        None => (mantissa, &mantissa[mantissa.len()..])
+    };
+    if integral.len() + fraction.len() == 0 || 
+        !integral.iter().chain(fraction.iter()).all(is_digit) {
+        return Err(ParseDecimalError::InvalidSyntax);
+    }
+    Ok((integral, fraction))
+}
+
+/// Parse a plain decimal number without sign into its digits and exponent.
+fn parse_decimal(text: &[u8]) -> Result<(Vec<u8>, i64), ParseDecimalError> {
+    let (mantissa, exponent) = match text.iter().position(|&b| b == b'e' || b == b'E') {
+        Some(i) => (&text[..i], parse_exponent(&text[i + 1..], MAX_DECIMAL_EXPONENT)?),
+        None => (text, 0)
+    };
+    let (integral, fraction) = split_mantissa(mantissa, u8::is_ascii_digit)?;
+
+    let digits = integral.iter().chain(fraction.iter()).map(|d| d - b'0').collect();
+    Ok((digits, exponent - fraction.len() as i64))
+}
+
+/// Multiply the given number, whose digits are least significant first, by `m`.
+fn multiply_small(digits: &mut Vec<u8>, m: u32, add: u32) {
+    let mut carry = add;
+    for d in digits.iter_mut() {
+        let value = u32::from(*d) * m + carry;
+        *d = (value % 10) as u8;
+        carry = value / 10;
+    }
+    while carry > 0 {
+        digits.push((carry % 10) as u8);
+        carry /= 10;
+    }
+}
+
+/// Parse a hexadecimal floating point number without sign and `0x` prefix into
+/// its decimal digits and exponent. The conversion is exact.
+fn parse_hex(text: &[u8]) -> Result<(Vec<u8>, i64), ParseDecimalError> {
+    let (mantissa, exponent) = match text.iter().position(|&b| b == b'p' || b == b'P') {
+        Some(i) => (&text[..i], parse_exponent(&text[i + 1..], MAX_BINARY_EXPONENT)?),
+        None => (text, 0)
+    };
+    let (integral, fraction) = split_mantissa(mantissa, u8::is_ascii_hexdigit)?;
+
+    let mut digits = Vec::new();
+    for &h in integral.iter().chain(fraction.iter()) {
+        multiply_small(&mut digits, 16, (h as char).to_digit(16).unwrap());
+    }
+
+    let binary_exponent = exponent - 4 * fraction.len() as i64;
+    if binary_exponent.abs() > MAX_BINARY_EXPONENT {
+        return Err(ParseDecimalError::ExponentOutOfRange);
+    }
+    // `2^-k = 5^k * 10^-k`, so a negative binary exponent is converted exactly.
+    let (factor, decimal_exponent) = if binary_exponent >= 0 {
+        (2, 0)
+    } else {
+        (5, binary_exponent)
+    };
+    for _ in 0..binary_exponent.abs() {
+        multiply_small(&mut digits, factor, 0);
+    }
+
+    digits.reverse();
+    Ok((digits, decimal_exponent))
+}
+
+/// Compare two magnitudes whose digits are least significant first and have
+/// no leading zeros.
+fn compare_magnitudes(lhs: &[u8], rhs: &[u8]) -> Ordering {
+    lhs.len().cmp(&rhs.len())
+        .then_with(|| lhs.iter().rev().cmp(rhs.iter().rev()))
+}
+
+/// Add or subtract two magnitudes whose digits are least significant first.
+/// When subtracting, `lhs` must not be less than `rhs`. The result has no
+/// leading zeros.
+fn combine_magnitudes(lhs: &[u8], rhs: &[u8], subtract: bool) -> Vec<u8> {
+    let mut result = Vec::with_capacity(lhs.len().max(rhs.len()) + 1);
+    let mut carry = 0i32;
+    for i in 0..lhs.len().max(rhs.len()) {
+        let l = i32::from(*lhs.get(i).unwrap_or(&0));
+        let r = i32::from(*rhs.get(i).unwrap_or(&0));
+        let mut value = if subtract { l - r } else { l + r } + carry;
+        carry = 0;
+        if value < 0 {
+            value += 10;
+            carry = -1;
+        } else if value >= 10 {
+            value -= 10;
+            carry = 1;
+        }
+        result.push(value as u8);
+    }
+    if carry > 0 {
+        result.push(carry as u8);
+    }
+    while result.last() == Some(&0) {
+        result.pop();
+    }
+    result
+}
+
+/// Compare two [`Decimal`] values exactly, considering them equal if they differ
+/// by at most `10^-precision`, i.e. `precision` is the number of decimal digits
+/// after the decimal point that are required to be correct. `rhs` and `lhs` are
+/// never rounded to binary floating point numbers.
+pub fn compare_decimals(lhs: &Decimal, rhs: &Decimal, precision: u32) -> Option<Ordering> {
+    if lhs.is_nan() || rhs.is_nan() {
+        return None;
+    }
+
+    // Infinities are ordered around finite numbers, and equal only to the
+    // infinity with the same sign.
+    let rank = |d: &Decimal| match (d.kind, d.negative) {
+        (DecimalKind::Infinity, true) => -1,
+        (DecimalKind::Infinity, false) => 1,
+        _ => 0
+    };
+    if lhs.is_infinite() || rhs.is_infinite() {
+        return Some(rank(lhs).cmp(&rank(rhs)));
+    }
+
+    let exponent = lhs.exponent.min(rhs.exponent);
+    let (l, r) = (lhs.aligned_digits(exponent), rhs.aligned_digits(exponent));
+    let (diff, ordering) = if lhs.negative != rhs.negative {
+        let ordering = if lhs.negative { Ordering::Less } else { Ordering::Greater };
+        (combine_magnitudes(&l, &r, false), ordering)
+    } else {
+        let ordering = compare_magnitudes(&l, &r);
+        let diff = match ordering {
+            Ordering::Less => combine_magnitudes(&r, &l, true),
+            _ => combine_magnitudes(&l, &r, true)
+        };
+        (diff, if lhs.negative { ordering.reverse() } else { ordering })
+    };
+
+    // `|lhs - rhs| = diff * 10^exponent <= 10^-precision` if and only if
+    // `diff <= 10^m` where `m = -precision - exponent`.
+    let m = -i64::from(precision) - exponent;
+    let within = if diff.is_empty() {
+        true
+    } else if m < 0 {
+        false
+    } else if (diff.len() as i64) <= m {
+        true
+    } else {
+        diff.len() as i64 == m + 1 && diff[diff.len() - 1] == 1 &&
+            diff[..diff.len() - 1].iter().all(|&d| d == 0)
+    };
+
+    if within {
+        Some(Ordering::Equal)
+    } else {
+        Some(ordering)
+    }
+}
+
 
 pub mod preclude {
-    pub use super::compare_floats;
+    pub use super::{compare_floats, compare_floats_rel, compare_floats_abs_or_rel};
+    pub use super::compare_floats_ulp;
+    pub use super::{compare_decimals, Decimal, DecimalSyntax, ParseDecimalError};
 }
 
 
@@ -50,7 +533,9 @@ pub mod preclude {
 mod tests {
     use std::cmp::Ordering;
 
-    use super::compare_floats;
+    use super::{compare_floats, compare_floats_rel, compare_floats_abs_or_rel};
+    use super::compare_floats_ulp;
+    use super::{compare_decimals, Decimal, DecimalSyntax, ParseDecimalError};
 
     #[test]
     fn test_compare_floats() {
@@ -60,20 +545,126 @@ mod tests {
         assert_eq!(compare_floats(1.0, 1.0 + 1e-8, 1e-6), Some(Ordering::Equal));
         assert_eq!(compare_floats(1.0, 1.0 + 1e-8, -1e-6), Some(Ordering::Equal));
         assert_eq!(compare_floats(1.0, 1.0 - 1e-8, 1e-6), Some(Ordering::Equal));
-        assert_eq!(compare_floats(1.0, std::f64::NAN, 1e-6), None);
-        assert_eq!(compare_floats(std::f64::NAN, 1.0, 1e-6), None);
-        assert_eq!(compare_floats(std::f64::NAN, std::f64::NAN, 1e-6), None);
-        assert_eq!(compare_floats(std::f64::INFINITY, 1.0, 1e-6), Some(Ordering::Greater));
-        assert_eq!(compare_floats(1.0, std::f64::INFINITY, 1e-6), Some(Ordering::Less));
-        assert_eq!(compare_floats(std::f64::INFINITY, std::f64::INFINITY, 1e-6), 
+        assert_eq!(compare_floats(1.0, f64::NAN, 1e-6), None);
+        assert_eq!(compare_floats(f64::NAN, 1.0, 1e-6), None);
+        assert_eq!(compare_floats(f64::NAN, f64::NAN, 1e-6), None);
+        assert_eq!(compare_floats(f64::INFINITY, 1.0, 1e-6), Some(Ordering::Greater));
+        assert_eq!(compare_floats(1.0, f64::INFINITY, 1e-6), Some(Ordering::Less));
+        assert_eq!(compare_floats(f64::INFINITY, f64::INFINITY, 1e-6), 
+            Some(Ordering::Equal));
+        assert_eq!(compare_floats(f64::NEG_INFINITY, 1.0, 1e-6), Some(Ordering::Less));
+        assert_eq!(compare_floats(1.0, f64::NEG_INFINITY, 1e-6), Some(Ordering::Greater));
+        assert_eq!(compare_floats(f64::NEG_INFINITY, f64::NEG_INFINITY, 1e-6), 
+            Some(Ordering::Equal));
+        assert_eq!(compare_floats(f64::INFINITY, f64::NEG_INFINITY, 1e-6), 
+            Some(Ordering::Greater));
+        assert_eq!(compare_floats(f64::NEG_INFINITY, f64::INFINITY, 1e-6), 
+            Some(Ordering::Less));
+    }
+
+    #[test]
+    fn test_compare_floats_rel() {
+        assert_eq!(compare_floats_rel(1e9 + 1.0, 1e9, 1e-6), Some(Ordering::Equal));
+        assert_eq!(compare_floats_rel(1e9 + 1e4, 1e9, 1e-6), Some(Ordering::Greater));
+        assert_eq!(compare_floats_rel(1e-9, 2e-9, 1e-6), Some(Ordering::Less));
+        assert_eq!(compare_floats_rel(0.0, 0.0, 1e-6), Some(Ordering::Equal));
+        assert_eq!(compare_floats_rel(1.0, f64::INFINITY, 1e-6), Some(Ordering::Less));
+        assert_eq!(compare_floats_rel(f64::INFINITY, f64::INFINITY, 1e-6), 
             Some(Ordering::Equal));
-        assert_eq!(compare_floats(std::f64::NEG_INFINITY, 1.0, 1e-6), Some(Ordering::Less));
-        assert_eq!(compare_floats(1.0, std::f64::NEG_INFINITY, 1e-6), Some(Ordering::Greater));
-        assert_eq!(compare_floats(std::f64::NEG_INFINITY, std::f64::NEG_INFINITY, 1e-6), 
+        assert_eq!(compare_floats_rel(f64::NAN, 1.0, 1e-6), None);
+    }
+
+    #[test]
+    fn test_compare_floats_abs_or_rel() {
+        assert_eq!(compare_floats_abs_or_rel(1e-9, 2e-9, 1e-6, 1e-6), Some(Ordering::Equal));
+        assert_eq!(compare_floats_abs_or_rel(1e9 + 1.0, 1e9, 1e-6, 1e-6), 
             Some(Ordering::Equal));
-        assert_eq!(compare_floats(std::f64::INFINITY, std::f64::NEG_INFINITY, 1e-6), 
+        assert_eq!(compare_floats_abs_or_rel(1.1, 1.0, 1e-6, 1e-6), Some(Ordering::Greater));
+        assert_eq!(compare_floats_abs_or_rel(-1.1, -1.0, 1e-6, 1e-6), Some(Ordering::Less));
+    }
+
+    #[test]
+    fn test_compare_floats_ulp() {
+        let next = f64::from_bits(1f64.to_bits() + 1);
+        assert_eq!(compare_floats_ulp(1.0, 1.0, 0), Some(Ordering::Equal));
+        assert_eq!(compare_floats_ulp(next, 1.0, 0), Some(Ordering::Greater));
+        assert_eq!(compare_floats_ulp(next, 1.0, 1), Some(Ordering::Equal));
+        assert_eq!(compare_floats_ulp(0.0, -0.0, 0), Some(Ordering::Equal));
+        assert_eq!(compare_floats_ulp(-f64::MIN_POSITIVE, f64::MIN_POSITIVE, 4), 
+            Some(Ordering::Less));
+        assert_eq!(compare_floats_ulp(f64::MAX, f64::INFINITY, 1), Some(Ordering::Less));
+        assert_eq!(compare_floats_ulp(f64::INFINITY, f64::MAX, u64::MAX), Some(Ordering::Greater));
+        assert_eq!(compare_floats_ulp(f64::NEG_INFINITY, f64::MIN, 1), Some(Ordering::Less));
+        assert_eq!(compare_floats_ulp(f64::INFINITY, f64::INFINITY, 0), Some(Ordering::Equal));
+        assert_eq!(compare_floats_ulp(f64::NEG_INFINITY, f64::INFINITY, u64::MAX), 
+            Some(Ordering::Less));
+        assert_eq!(compare_floats_ulp(f64::NAN, 1.0, 1), None);
+    }
+
+    fn decimal(text: &str) -> Decimal {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_decimal() {
+        assert_eq!(decimal("0.1500").to_string(), "0.15");
+        assert_eq!(decimal("-120").to_string(), "-120");
+        assert_eq!(decimal("-0.000").to_string(), "0");
+        assert_eq!(decimal(".5e1").to_string(), "5");
+        assert_eq!(decimal("12.5e-3").to_string(), "0.0125");
+        assert_eq!(decimal("-1.25e-100").to_string(), "-1.25e-100");
+        assert_eq!(decimal("1e40").to_string(), "1e40");
+        assert_eq!(decimal("0x1.8p1").to_string(), "3");
+        assert_eq!(decimal("0x.1").to_string(), "0.0625");
+        assert!(decimal("-Infinity").is_infinite());
+        assert!(decimal("NaN").is_nan());
+
+        assert_eq!("".parse::<Decimal>(), Err(ParseDecimalError::InvalidSyntax));
+        assert_eq!(".".parse::<Decimal>(), Err(ParseDecimalError::InvalidSyntax));
+        assert_eq!("1e".parse::<Decimal>(), Err(ParseDecimalError::InvalidSyntax));
+        assert_eq!("1.2.3".parse::<Decimal>(), Err(ParseDecimalError::InvalidSyntax));
+        assert_eq!("1e1000000".parse::<Decimal>(), Err(ParseDecimalError::ExponentOutOfRange));
+    }
+
+    #[test]
+    fn test_parse_decimal_strict() {
+        let strict = DecimalSyntax::strict();
+        assert_eq!(Decimal::parse("-1.5e3", &strict), Ok(decimal("-1500")));
+        assert_eq!(Decimal::parse("+1", &strict), Err(ParseDecimalError::PlusSign));
+        assert_eq!(Decimal::parse("inf", &strict), Err(ParseDecimalError::SpecialValue));
+        assert_eq!(Decimal::parse("-nan", &strict), Err(ParseDecimalError::SpecialValue));
+        assert_eq!(Decimal::parse("0x10", &strict), Err(ParseDecimalError::HexFloat));
+    }
+
+    #[test]
+    fn test_compare_decimals() {
+        let exact = decimal("0.1");
+        let printed = decimal("0.1000000000000000055511");
+        assert_eq!(compare_decimals(&printed, &exact, 18), Some(Ordering::Greater));
+        assert_eq!(compare_decimals(&printed, &exact, 17), Some(Ordering::Equal));
+
+        assert_eq!(compare_decimals(&decimal("12345678901234567890"), 
+            &decimal("12345678901234567891"), 0), Some(Ordering::Equal));
+        assert_eq!(compare_decimals(&decimal("12345678901234567890"), 
+            &decimal("12345678901234567892"), 0), Some(Ordering::Less));
+        assert_eq!(compare_decimals(&decimal("1.5"), &decimal("1.4"), 1), Some(Ordering::Equal));
+        assert_eq!(compare_decimals(&decimal("1.51"), &decimal("1.4"), 1), 
             Some(Ordering::Greater));
-        assert_eq!(compare_floats(std::f64::NEG_INFINITY, std::f64::INFINITY, 1e-6), 
+        assert_eq!(compare_decimals(&decimal("-0.05"), &decimal("0.05"), 1), 
+            Some(Ordering::Equal));
+        assert_eq!(compare_decimals(&decimal("-0.05"), &decimal("0.06"), 1), 
             Some(Ordering::Less));
+        assert_eq!(compare_decimals(&decimal("-2"), &decimal("-4"), 0), 
+            Some(Ordering::Greater));
+        assert_eq!(compare_decimals(&decimal("0x1.8p1"), &decimal("3"), 30), 
+            Some(Ordering::Equal));
+        assert_eq!(compare_decimals(&decimal("1e100000"), &decimal("1e-100000"), 6), 
+            Some(Ordering::Greater));
+
+        assert_eq!(compare_decimals(&decimal("inf"), &decimal("1e100"), 6), 
+            Some(Ordering::Greater));
+        assert_eq!(compare_decimals(&decimal("-inf"), &decimal("-inf"), 6), 
+            Some(Ordering::Equal));
+        assert_eq!(compare_decimals(&decimal("nan"), &decimal("nan"), 6), None);
     }
 }
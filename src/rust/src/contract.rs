@@ -1,33 +1,215 @@
+use std::borrow::Cow;
 use std::str::FromStr;
 use std::string::ToString;
-use std::fmt::{Display, Debug};
+use std::fmt::{Display, Debug, Formatter};
 use std::cmp::Ordering;
-use std::process::exit;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::io::Read;
 
-use crate::tokenized::TokenizedRead;
-use crate::cmp::compare_floats;
+use crate::tokenized::{NumberError, NumberFormat, Position, TokenError, TokenErrorKind};
+use crate::tokenized::{PeekRead, TokenizedRead};
+use crate::strict::StrictReader;
+use crate::cmp::{compare_floats, compare_floats_rel, compare_floats_abs_or_rel};
+use crate::cmp::compare_floats_ulp;
+use crate::cmp::{compare_decimals, Decimal, DecimalSyntax};
+use crate::report::{Report, submit, append_feedback, append_judge_log};
 
 
 /// Exit code of the process that indicates an `Accepted` result.
 pub const EXIT_ACCEPTED: i32 = 0;
 
-/// Exit code of the process that indicates a `Rejected` result.
+/// Exit code of the process that indicates a `Rejected` result, i.e. a
+/// `Wrong Answer` verdict.
 pub const EXIT_REJECTED: i32 = -1;
 
+/// Exit code of the process that indicates the judge itself failed, e.g. the
+/// command line arguments are invalid or the judge files cannot be opened.
+pub const EXIT_JUDGE_FAILURE: i32 = -2;
+
+/// Exit code of the process that indicates a `Presentation Error` result.
+pub const EXIT_PRESENTATION_ERROR: i32 = -3;
+
+/// Exit code of the process that indicates a `Partial Score` result.
+pub const EXIT_PARTIAL_SCORE: i32 = -4;
+
+/// Exit code of the process that indicates an `Invalid Input` result, which is
+/// reported by input validators.
+pub const EXIT_INVALID_INPUT: i32 = -5;
+
+/// Verdict of a judge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Verdict {
+    /// The user's solution is correct.
+    Accepted,
+
+    /// The user's solution is wrong.
+    WrongAnswer,
+
+    /// The user's output is correct in content but malformed in format.
+    PresentationError,
+
+    /// The user's solution is partially correct and earns part of the score.
+    PartialScore,
+
+    /// The judge itself failed, e.g. a bug in the checker or an invalid
+    /// standard answer. This verdict should never be shown to contestants as
+    /// a wrong answer.
+    JudgeFailure,
+
+    /// The test input violates the constraints of the problem. This verdict is
+    /// reported by input validators.
+    InvalidInput
+}
+
+impl Verdict {
+    /// Get the exit code of the process that indicates this verdict.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Verdict::Accepted => EXIT_ACCEPTED,
+            Verdict::WrongAnswer => EXIT_REJECTED,
+            Verdict::PresentationError => EXIT_PRESENTATION_ERROR,
+            Verdict::PartialScore => EXIT_PARTIAL_SCORE,
+            Verdict::JudgeFailure => EXIT_JUDGE_FAILURE,
+            Verdict::InvalidInput => EXIT_INVALID_INPUT
+        }
+    }
+
+    /// Get the human readable name of this verdict.
+    pub fn message(self) -> &'static str {
+        match self {
+            Verdict::Accepted => "Accepted",
+            Verdict::WrongAnswer => "Wrong Answer",
+            Verdict::PresentationError => "Presentation Error",
+            Verdict::PartialScore => "Partial Score",
+            Verdict::JudgeFailure => "Judge Failure",
+            Verdict::InvalidInput => "Invalid Input"
+        }
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+/// Report the given verdict, score and message to the judge system and exit
+/// the program. See [`crate::report::submit`] for how the result is reported.
+fn terminate(verdict: Verdict, score: Option<f64>, message: Option<&str>) -> ! {
+    submit(Report::new(verdict, score, message))
+}
+
+/// Exit the program with the given verdict. The message, if any, is reported
+/// along with the verdict.
+pub fn quit(verdict: Verdict, message: Option<&str>) -> ! {
+    terminate(verdict, None, message)
+}
+
 /// Exit the program with an accepted result.
-pub fn accept<'a>(message: Option<&'a str>) -> ! {
-    match message {
-        Some(msg) => eprintln!("Accepted: {}", msg),
-        None => eprintln!("Accepted.")
-    };
-    exit(EXIT_ACCEPTED)
+pub fn accept(message: Option<&str>) -> ! {
+    quit(Verdict::Accepted, message)
 }
 
 /// Exit the program with a rejected result. The reason why the solution is
 /// rejected should be included in the given message.
-pub fn reject<'a>(message: &'a str) -> ! {
-    eprintln!("Rejected: {}", message);
-    exit(EXIT_REJECTED)
+pub fn reject(message: &str) -> ! {
+    quit(Verdict::WrongAnswer, Some(message))
+}
+
+/// Exit the program with a presentation error result.
+pub fn presentation_error(message: &str) -> ! {
+    quit(Verdict::PresentationError, Some(message))
+}
+
+/// Exit the program with a partial score result. The score should be a
+/// fraction of the full score of the test, in the range of `[0, 1]`. Exits the
+/// program with a judge failure result if the score is out of range.
+pub fn partial(score: f64, message: Option<&str>) -> ! {
+    if !(0.0..=1.0).contains(&score) {
+        judge_fail(&format!("partial score out of range [0, 1]: {}", score));
+    }
+    terminate(Verdict::PartialScore, Some(score), message)
+}
+
+/// Exit the program with a partial score result, which is given as the points
+/// earned out of the maximum points of the test. The points are reported along
+/// with the score `points / max_points`. Exits the program with a judge failure
+/// result if `max_points` is not positive or `points` is out of the range of
+/// `[0, max_points]`.
+pub fn partial_points(points: f64, max_points: f64, message: Option<&str>) -> ! {
+    if !(max_points > 0.0 && max_points.is_finite()) {
+        judge_fail(&format!("maximum points must be positive: {}", max_points));
+    }
+    if !(0.0..=max_points).contains(&points) {
+        judge_fail(&format!("partial points out of range [0, {}]: {}", 
+            max_points, points));
+    }
+    submit(Report::new(Verdict::PartialScore, Some(points / max_points), message)
+        .with_points(points, max_points))
+}
+
+/// Exit the program with an invalid input result. This should be used by
+/// input validators when the test input violates the constraints.
+pub fn invalid_input(message: &str) -> ! {
+    quit(Verdict::InvalidInput, Some(message))
+}
+
+/// Exit the program with a judge failure result. This should be used when
+/// the failure is caused by the judge rather than the user's solution.
+pub fn judge_fail(message: &str) -> ! {
+    quit(Verdict::JudgeFailure, Some(message))
+}
+
+/// Append a line to the feedback that will be shown to contestants. The
+/// accumulated feedback is reported along with the final verdict.
+pub fn feedback(message: &str) {
+    append_feedback(message);
+}
+
+/// Append a line to the diagnostic log that will be shown to the jury only,
+/// e.g. the expected and found values or the internal state of the checker.
+/// The accumulated log is reported along with the final verdict through a
+/// channel hidden from contestants, i.e. the judge log file under the
+/// [`Protocol::Wave`](crate::report::Protocol::Wave) protocol, which is set by
+/// the `WAVE_JUDGE_LOG` environment variable or the `--judge-log` option, or
+/// `judgemessage.txt` under the Kattis protocol. It is also included in the
+/// result file if one is set.
+pub fn judge_log(message: &str) {
+    append_judge_log(message);
+}
+
+/// Macro that provide simplified access to the [`feedback`] function. The 
+/// arguments to this macro are the same as those arguments to the `format!` 
+/// macro.
+#[macro_export]
+macro_rules! feedback {
+    ($($arg:tt)*) => {
+        $crate::contract::feedback(format!($($arg)*).as_str())
+    };
+}
+
+/// Macro that provide simplified access to the [`judge_log`] function. The 
+/// arguments to this macro are the same as those arguments to the `format!` 
+/// macro.
+#[macro_export]
+macro_rules! judge_log {
+    ($($arg:tt)*) => {
+        $crate::contract::judge_log(format!($($arg)*).as_str())
+    };
+}
+
+/// Macro that provide simplified access to the [`quit`] function. The first
+/// argument is the [`Verdict`], and the remaining arguments can be empty or
+/// the same as those arguments to the `format!` macro.
+#[macro_export]
+macro_rules! quit {
+    ($verdict:expr) => {
+        $crate::contract::quit($verdict, None)
+    };
+    ($verdict:expr, $($arg:tt)*) => {
+        $crate::contract::quit($verdict, Some(format!($($arg)*).as_str()))
+    };
 }
 
 /// Macro that provide simplified access to the [`accept`] function. The 
@@ -36,10 +218,10 @@ pub fn reject<'a>(message: &'a str) -> ! {
 #[macro_export]
 macro_rules! accept {
     () => {
-        $crate::contract::accept(None);
+        $crate::contract::accept(None)
     };
     ($($arg:tt)*) => {
-        $crate::contract::accept(Some(format!($($arg)*).as_str()));
+        $crate::contract::accept(Some(format!($($arg)*).as_str()))
     };
 }
 
@@ -49,24 +231,270 @@ macro_rules! accept {
 #[macro_export]
 macro_rules! reject {
     ($($arg:tt)*) => {
-        $crate::contract::reject(format!($($arg)*).as_str());
+        $crate::contract::reject(format!($($arg)*).as_str())
+    };
+}
+
+/// Macro that provide simplified access to the [`partial`] function. The first
+/// argument is the score, and the remaining arguments can be empty or the same
+/// as those arguments to the `format!` macro.
+#[macro_export]
+macro_rules! partial {
+    ($score:expr) => {
+        $crate::contract::partial($score, None)
+    };
+    ($score:expr, $($arg:tt)*) => {
+        $crate::contract::partial($score, Some(format!($($arg)*).as_str()))
+    };
+}
+
+/// Macro that provide simplified access to the [`presentation_error`] function. 
+/// The arguments to this macro are the same as those arguments to the 
+/// `format!` macro.
+#[macro_export]
+macro_rules! presentation_error {
+    ($($arg:tt)*) => {
+        $crate::contract::presentation_error(format!($($arg)*).as_str())
     };
 }
 
+/// Macro that provide simplified access to the [`invalid_input`] function. The 
+/// arguments to this macro are the same as those arguments to the `format!` 
+/// macro.
+#[macro_export]
+macro_rules! invalid_input {
+    ($($arg:tt)*) => {
+        $crate::contract::invalid_input(format!($($arg)*).as_str())
+    };
+}
+
+/// Macro that provide simplified access to the [`judge_fail`] function. The 
+/// arguments to this macro are the same as those arguments to the `format!` 
+/// macro.
+#[macro_export]
+macro_rules! judge_fail {
+    ($($arg:tt)*) => {
+        $crate::contract::judge_fail(format!($($arg)*).as_str())
+    };
+}
+
+/// Kind of a [`ReadError`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ReadErrorKind {
+    /// EOF is hit while a token is expected.
+    UnexpectedEof,
+
+    /// The token cannot be converted to the expected type.
+    InvalidToken,
+
+    /// The token is not the expected token.
+    TokenMismatch,
+
+    /// The value converted from the token does not satisfy the expectation.
+    UnexpectedValue,
+
+    /// The value converted from the token is out of the expected range.
+    OutOfRange,
+
+    /// A token is found while EOF is expected.
+    TrailingData,
+
+    /// The content violates the format required by the underlying reader, e.g.
+    /// unexpected whitespace characters read by a [`StrictReader`].
+    InvalidFormat,
+
+    /// The content is not valid UTF-8, e.g. stray binary bytes in the output.
+    InvalidUtf8
+}
+
+/// Error raised when the content read from a [`JudgeReader`] does not meet the
+/// expectation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReadError {
+    /// Kind of the error.
+    pub kind: ReadErrorKind,
+
+    /// Description of what is expected, if any.
+    pub expected: Option<String>,
+
+    /// The token found, if any.
+    pub found: Option<String>,
+
+    /// Position where the error occurs, if known.
+    pub position: Option<Position>,
+
+    /// Name of the variable being read, if any.
+    pub name: Option<String>
+}
+
+impl ReadError {
+    /// Create a new [`ReadError`] instance.
+    pub fn new(kind: ReadErrorKind, expected: Option<String>, found: Option<String>) 
+        -> ReadError {
+        ReadError { kind, expected, found, position: None, name: None }
+    }
+
+    /// Create a new [`ReadError`] instance indicating that the value `found` of
+    /// the variable `name` is out of the range of `[lo, hi]`.
+    pub fn out_of_range<U: Display>(lo: &U, hi: &U, name: &str, found: String) -> ReadError {
+        ReadError::new(ReadErrorKind::OutOfRange,
+            Some(format!("{} \u{2264} {} \u{2264} {}", lo, name, hi)), Some(found))
+            .with_name(name)
+    }
+
+    /// Attach the position where the error occurs to this error.
+    pub fn with_position(mut self, position: Position) -> ReadError {
+        self.position = Some(position);
+        self
+    }
+
+    /// Attach the name of the variable being read to this error.
+    pub fn with_name(mut self, name: &str) -> ReadError {
+        self.name = Some(String::from(name));
+        self
+    }
+}
+
+impl Display for ReadError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let expected = self.expected.as_deref().unwrap_or("");
+        let found = self.found.as_deref().unwrap_or("");
+        match self.kind {
+            ReadErrorKind::UnexpectedEof => f.write_str("Unexpected EOF")?,
+            ReadErrorKind::InvalidToken => match &self.expected {
+                Some(reason) => write!(f, "Unexpected token: \"{}\": {}", found, reason)?,
+                None => write!(f, "Unexpected token: \"{}\"", found)?
+            },
+            ReadErrorKind::TokenMismatch => 
+                write!(f, "expect \"{}\", found \"{}\"", expected, found)?,
+            ReadErrorKind::UnexpectedValue => 
+                write!(f, "Unexpected value: \"{}\": {}", found, expected)?,
+            ReadErrorKind::TrailingData => 
+                write!(f, "EOF expected, but found \"{}\"", found)?,
+            ReadErrorKind::OutOfRange => write!(f, "{} = {} violates {}", 
+                self.name.as_deref().unwrap_or("value"), found, expected)?,
+            ReadErrorKind::InvalidFormat => write!(f, "Invalid format: {}", expected)?,
+            ReadErrorKind::InvalidUtf8 => write!(f, "Invalid UTF-8: {}", expected)?
+        };
+        match (self.kind, &self.name) {
+            (ReadErrorKind::OutOfRange, _) | (_, None) => (),
+            (_, Some(name)) => write!(f, " while reading {}", name)?
+        };
+        match self.position {
+            Some(position) => write!(f, " ({})", position),
+            None => Ok(())
+        }
+    }
+}
+
+impl std::error::Error for ReadError { }
+
+impl From<TokenError> for ReadError {
+    fn from(err: TokenError) -> ReadError {
+        let kind = match err.kind {
+            TokenErrorKind::InvalidFormat => ReadErrorKind::InvalidFormat,
+            TokenErrorKind::InvalidUtf8 => ReadErrorKind::InvalidUtf8
+        };
+        ReadError::new(kind, Some(err.message), None).with_position(err.position)
+    }
+}
+
+/// Maximum number of elements reserved up front by the bulk reads on
+/// [`JudgeReader`]. The number of elements usually comes from the content being
+/// judged, so longer sequences grow as their elements are actually read.
+const MAX_RESERVED_ELEMENTS: usize = 1 << 16;
+
 /// Provide a formatted reader that can be used in judge.
 pub struct JudgeReader<T: TokenizedRead> {
-    inner: T
+    inner: T,
+    failure_verdict: Verdict,
+    number_format: NumberFormat
 }
 
 impl<T: TokenizedRead> JudgeReader<T> {
+    /// Create a new [`JudgeReader`] instance wrapping around the given
+    /// [`TokenizedRead`] instance. Any expectation failed on the reader exits
+    /// the program with a [`Verdict::WrongAnswer`] verdict.
+    pub fn new(inner: T) -> JudgeReader<T> {
+        JudgeReader::with_failure_verdict(inner, Verdict::WrongAnswer)
+    }
+
+    /// Create a new [`JudgeReader`] instance wrapping around the given
+    /// [`TokenizedRead`] instance. Any expectation failed on the reader exits
+    /// the program with the given verdict. Readers around judge files such as
+    /// the standard answer should use [`Verdict::JudgeFailure`].
+    pub fn with_failure_verdict(inner: T, failure_verdict: Verdict) -> JudgeReader<T> {
+        JudgeReader { inner, failure_verdict, number_format: NumberFormat::default() }
+    }
+
+    /// Get the verdict used when an expectation fails on this reader.
+    pub fn failure_verdict(&self) -> Verdict {
+        self.failure_verdict
+    }
+
+    /// Set the verdict used when an expectation fails on this reader.
+    pub fn set_failure_verdict(&mut self, verdict: Verdict) {
+        self.failure_verdict = verdict;
+    }
+
+    /// Get the syntax accepted by the numeric expectations such as
+    /// [`JudgeReader::expect_i64`] on this reader.
+    pub fn number_format(&self) -> NumberFormat {
+        self.number_format
+    }
+
+    /// Set the syntax accepted by the numeric expectations such as
+    /// [`JudgeReader::expect_i64`] on this reader.
+    pub fn set_number_format(&mut self, format: NumberFormat) {
+        self.number_format = format;
+    }
+
     /// Get the inner reader of the [`JudgeReader`] instance.
     pub fn inner_reader(&mut self) -> &mut T {
         &mut self.inner
     }
 
-    /// Read one token from the underlying reader.
+    /// Get the position of the token or line returned by the most recent read on
+    /// the underlying reader.
+    pub fn position(&self) -> Position {
+        self.inner.position()
+    }
+
+    /// Read one token from the underlying reader. Exits the program with the
+    /// failure verdict of this reader if the content violates the format
+    /// required by the underlying reader, e.g. the token is not valid UTF-8.
     pub fn read_token(&mut self) -> Option<String> {
-        self.inner.read_token()
+        let result = self.inner.try_read_token().map_err(ReadError::from);
+        self.unwrap_or_fail(result)
+    }
+
+    /// Read one token from the underlying reader. The token may be borrowed from
+    /// the internal buffer of the underlying reader. Exits the program with the
+    /// failure verdict of this reader if the content violates the format
+    /// required by the underlying reader.
+    pub fn read_token_ref(&mut self) -> Option<Cow<'_, str>> {
+        let verdict = self.failure_verdict;
+        match self.inner.try_read_token_ref() {
+            Ok(token) => token,
+            Err(err) => quit(verdict, Some(ReadError::from(err).to_string().as_str()))
+        }
+    }
+
+    /// Read the raw bytes of one token from the underlying reader, which are not
+    /// required to be valid UTF-8. Exits the program with the failure verdict of
+    /// this reader if the content violates the format required by the
+    /// underlying reader.
+    pub fn read_token_bytes(&mut self) -> Option<Vec<u8>> {
+        let result = self.inner.try_read_token_bytes().map_err(ReadError::from);
+        self.unwrap_or_fail(result)
+    }
+
+    /// Read one line from the underlying reader. Exits the program with the
+    /// failure verdict of this reader if the content violates the format
+    /// required by the underlying reader, e.g. the line is not valid UTF-8.
+    pub fn read_line(&mut self) -> Option<String> {
+        let result = self.inner.try_read_line().map_err(ReadError::from);
+        self.unwrap_or_fail(result)
     }
 
     /// Read one token from the underlying reader and convert it to the given
@@ -80,15 +508,49 @@ impl<T: TokenizedRead> JudgeReader<T> {
                 .expect("failed to convert token to the given type."))
     }
 
+    /// Read one line from the underlying reader and split it into tokens
+    /// separated by whitespace characters. Returns `None` if the underlying
+    /// reader returns `None`.
+    pub fn read_line_tokens(&mut self) -> Option<Vec<String>> {
+        self.read_line()
+            .map(|line| line.split_whitespace().map(String::from).collect())
+    }
+
+    /// Exit the program with the failure verdict of this reader, reporting the
+    /// given error.
+    fn fail(&self, err: &ReadError) -> ! {
+        quit(self.failure_verdict, Some(err.to_string().as_str()))
+    }
+
+    /// Unwrap the given result, or exit the program with the failure verdict of
+    /// this reader if the result is an error.
+    pub(crate) fn unwrap_or_fail<U>(&self, result: Result<U, ReadError>) -> U {
+        match result {
+            Ok(value) => value,
+            Err(err) => self.fail(&err)
+        }
+    }
+
+    /// Create a new [`ReadError`] at the position of the most recently read token.
+    fn error(&self, kind: ReadErrorKind, expected: Option<String>, found: Option<String>) 
+        -> ReadError {
+        ReadError::new(kind, expected, found).with_position(self.inner.position())
+    }
+
+    /// Read the next token from the inner reader, expecting that EOF has not
+    /// been hit yet.
+    fn try_next_token(&mut self) -> Result<String, ReadError> {
+        self.inner.try_read_token()?
+            .ok_or_else(|| self.error(ReadErrorKind::UnexpectedEof, None, None))
+    }
+
     /// Expect the next token from the inner reader to be the given value's 
     /// string representation. If `ignore_case` is true, then a string 
     /// comparison ignoring ASCII case will be performed.
-    pub fn expect_token<U>(&mut self, expected: &U, ignore_case: bool) -> String
+    pub fn try_expect_token<U>(&mut self, expected: &U, ignore_case: bool) 
+        -> Result<String, ReadError>
         where U: ?Sized + ToString {
-        let token = match self.inner.read_token() {
-            Some(token) => token,
-            None => reject!("Unexpected EOF.")
-        };
+        let token = self.try_next_token()?;
         let expected = expected.to_string();
         
         let pass = 
@@ -99,23 +561,113 @@ impl<T: TokenizedRead> JudgeReader<T> {
             };
         
         if !pass {
-            reject!("expect \"{}\", found \"{}\"", expected, token);
+            return Err(self.error(ReadErrorKind::TokenMismatch, 
+                Some(expected), Some(token)));
         }
 
-        token
+        Ok(token)
+    }
+
+    /// Expect the next token from the inner reader to be the given value's 
+    /// string representation. If `ignore_case` is true, then a string 
+    /// comparison ignoring ASCII case will be performed.
+    pub fn expect_token<U>(&mut self, expected: &U, ignore_case: bool) -> String
+        where U: ?Sized + ToString {
+        let result = self.try_expect_token(expected, ignore_case);
+        self.unwrap_or_fail(result)
     }
 
     /// Expect the next token from the inner reader can be converted to the 
     /// given type.
-    pub fn expect_type<U>(&mut self) -> U
+    pub fn try_expect_type<U>(&mut self) -> Result<U, ReadError>
         where U: FromStr {
-        let token = match self.inner.read_token() {
+        // Parse the borrowed token to avoid allocation on the hot path.
+        let token = match self.inner.try_read_token_ref()? {
             Some(token) => token,
-            None => reject!("Unexpected EOF.")
+            None => return Err(self.error(ReadErrorKind::UnexpectedEof, None, None))
         };
-        match U::from_str(token.as_str()) {
-            Ok(value) => value,
-            Err(..) => reject!("Unexpected token: \"{}\"", token)
+        match U::from_str(&token) {
+            Ok(value) => Ok(value),
+            Err(..) => {
+                let token = token.into_owned();
+                Err(self.error(ReadErrorKind::InvalidToken, None, Some(token)))
+            }
+        }
+    }
+
+    /// Expect the next token from the inner reader can be converted to the 
+    /// given type.
+    pub fn expect_type<U>(&mut self) -> U
+        where U: FromStr {
+        let result = self.try_expect_type();
+        self.unwrap_or_fail(result)
+    }
+
+    /// Convert the result of a numeric read on the inner reader.
+    fn number_result<U>(&self, result: Result<Option<U>, NumberError>) -> Result<U, ReadError> {
+        match result {
+            Ok(Some(value)) => Ok(value),
+            Ok(None) => Err(self.error(ReadErrorKind::UnexpectedEof, None, None)),
+            Err(NumberError::Token(err)) => Err(ReadError::from(err)),
+            Err(NumberError::Invalid { token, reason }) => Err(self.error(
+                ReadErrorKind::InvalidToken, Some(String::from(reason)), Some(token)))
+        }
+    }
+
+    /// Expect the next token from the inner reader is an `i64` in the number
+    /// format of this reader. The token is parsed directly from its raw bytes.
+    pub fn try_expect_i64(&mut self) -> Result<i64, ReadError> {
+        let result = self.inner.read_i64(&self.number_format);
+        self.number_result(result)
+    }
+
+    /// Expect the next token from the inner reader is an `i64` in the number
+    /// format of this reader.
+    pub fn expect_i64(&mut self) -> i64 {
+        let result = self.try_expect_i64();
+        self.unwrap_or_fail(result)
+    }
+
+    /// Expect the next token from the inner reader is a `u64` in the number
+    /// format of this reader. The token is parsed directly from its raw bytes.
+    pub fn try_expect_u64(&mut self) -> Result<u64, ReadError> {
+        let result = self.inner.read_u64(&self.number_format);
+        self.number_result(result)
+    }
+
+    /// Expect the next token from the inner reader is a `u64` in the number
+    /// format of this reader.
+    pub fn expect_u64(&mut self) -> u64 {
+        let result = self.try_expect_u64();
+        self.unwrap_or_fail(result)
+    }
+
+    /// Expect the next token from the inner reader is an `f64` in the number
+    /// format of this reader. The token is parsed directly from its raw bytes,
+    /// and `inf`, `nan` and hexadecimal floating point numbers are rejected.
+    pub fn try_expect_f64(&mut self) -> Result<f64, ReadError> {
+        let result = self.inner.read_f64(&self.number_format);
+        self.number_result(result)
+    }
+
+    /// Expect the next token from the inner reader is an `f64` in the number
+    /// format of this reader.
+    pub fn expect_f64(&mut self) -> f64 {
+        let result = self.try_expect_f64();
+        self.unwrap_or_fail(result)
+    }
+
+    /// Expect the next token from the inner reader can be converted to the 
+    /// given type and satisfies the given predicate.
+    pub fn try_expect_value_that<U, F, E>(&mut self, predicate: F) -> Result<U, ReadError>
+        where U: FromStr + Display,
+              F: FnOnce(&U) -> Result<(), E>,
+              E: Debug {
+        let token_value = self.try_expect_type::<U>()?;
+        match predicate(&token_value) {
+            Ok(..) => Ok(token_value),
+            Err(err) => Err(self.error(ReadErrorKind::UnexpectedValue, 
+                Some(format!("{:?}", err)), Some(token_value.to_string())))
         }
     }
 
@@ -125,52 +677,278 @@ impl<T: TokenizedRead> JudgeReader<T> {
         where U: FromStr + Display,
               F: FnOnce(&U) -> Result<(), E>,
               E: Debug {
-        let token = match self.inner.read_token() {
-            Some(token) => token,
-            None => reject!("Unexpected EOF.")
+        let result = self.try_expect_value_that(predicate);
+        self.unwrap_or_fail(result)
+    }
+
+    /// Expect the next token from the inner reader can be converted to the 
+    /// given type and lies in the range of `[lo, hi]`. `name` is the name of the
+    /// variable being read, which is used in error messages.
+    pub fn try_expect_int_in_range<U>(&mut self, lo: U, hi: U, name: &str) 
+        -> Result<U, ReadError>
+        where U: FromStr + Ord + Display {
+        let token = self.try_next_token()?;
+        let value = match U::from_str(token.as_str()) {
+            Ok(value) => value,
+            Err(..) => return Err(self.error(ReadErrorKind::InvalidToken, None, Some(token))
+                .with_name(name))
         };
-        match U::from_str(token.as_str()) {
-            Ok(token_value) => {
-                match predicate(&token_value) {
-                    Ok(..) => token_value,
-                    Err(err) => {
-                        reject!("Unexpected value: \"{}\": {:?}", 
-                            token_value, err)
-                    }
-                }
-            },
-            Err(..) => reject!("Unexpected token: \"{}\"", token)
+        if value < lo || value > hi {
+            return Err(ReadError::out_of_range(&lo, &hi, name, token)
+                .with_position(self.inner.position()));
         }
+        Ok(value)
+    }
+
+    /// Expect the next token from the inner reader can be converted to the 
+    /// given type and lies in the range of `[lo, hi]`.
+    pub fn expect_int_in_range<U>(&mut self, lo: U, hi: U, name: &str) -> U
+        where U: FromStr + Ord + Display {
+        let result = self.try_expect_int_in_range(lo, hi, name);
+        self.unwrap_or_fail(result)
+    }
+
+    /// Expect the next token from the inner reader can be converted to `f64`
+    /// and lies in the range of `[lo, hi]`. NaN is never in range.
+    pub fn try_expect_float_in_range(&mut self, lo: f64, hi: f64, name: &str) 
+        -> Result<f64, ReadError> {
+        let token = self.try_next_token()?;
+        let value = match f64::from_str(token.as_str()) {
+            Ok(value) => value,
+            Err(..) => return Err(self.error(ReadErrorKind::InvalidToken, None, Some(token))
+                .with_name(name))
+        };
+        if !(lo <= value && value <= hi) {
+            return Err(ReadError::out_of_range(&lo, &hi, name, token)
+                .with_position(self.inner.position()));
+        }
+        Ok(value)
+    }
+
+    /// Expect the next token from the inner reader can be converted to `f64`
+    /// and lies in the range of `[lo, hi]`.
+    pub fn expect_float_in_range(&mut self, lo: f64, hi: f64, name: &str) -> f64 {
+        let result = self.try_expect_float_in_range(lo, hi, name);
+        self.unwrap_or_fail(result)
+    }
+
+    /// Expect the next `n` tokens from the inner reader can be converted to the
+    /// given type. `name` is the name of the sequence, and errors name the
+    /// offending element by its 1-based index, e.g. `element 37 of a`.
+    pub fn try_expect_vec<U>(&mut self, n: usize, name: &str) -> Result<Vec<U>, ReadError>
+        where U: FromStr {
+        let mut values = Vec::with_capacity(n.min(MAX_RESERVED_ELEMENTS));
+        for i in 1..=n {
+            let value = self.try_expect_type::<U>()
+                .map_err(|err| err.with_name(&format!("element {} of {}", i, name)))?;
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    /// Expect the next `n` tokens from the inner reader can be converted to the
+    /// given type.
+    pub fn expect_vec<U>(&mut self, n: usize, name: &str) -> Vec<U>
+        where U: FromStr {
+        let result = self.try_expect_vec(n, name);
+        self.unwrap_or_fail(result)
+    }
+
+    /// Expect the next `n` tokens from the inner reader can be converted to the
+    /// given type and each lies in the range of `[lo, hi]`.
+    pub fn try_expect_vec_in_range<U>(&mut self, n: usize, lo: U, hi: U, name: &str) 
+        -> Result<Vec<U>, ReadError>
+        where U: FromStr + Ord + Display + Clone {
+        let bound_name = format!("{}[i]", name);
+        let mut values = Vec::with_capacity(n.min(MAX_RESERVED_ELEMENTS));
+        for i in 1..=n {
+            let value = self.try_expect_int_in_range(lo.clone(), hi.clone(), &bound_name)
+                .map_err(|err| err.with_name(&format!("element {} of {}", i, name)))?;
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    /// Expect the next `n` tokens from the inner reader can be converted to the
+    /// given type and each lies in the range of `[lo, hi]`.
+    pub fn expect_vec_in_range<U>(&mut self, n: usize, lo: U, hi: U, name: &str) -> Vec<U>
+        where U: FromStr + Ord + Display + Clone {
+        let result = self.try_expect_vec_in_range(n, lo, hi, name);
+        self.unwrap_or_fail(result)
+    }
+
+    /// Expect the next `rows * cols` tokens from the inner reader can be
+    /// converted to the given type, in row-major order. Errors name the
+    /// offending element by its 1-based row and column, e.g. `element (2, 3) of a`.
+    pub fn try_expect_matrix<U>(&mut self, rows: usize, cols: usize, name: &str) 
+        -> Result<Vec<Vec<U>>, ReadError>
+        where U: FromStr {
+        let mut matrix = Vec::with_capacity(rows.min(MAX_RESERVED_ELEMENTS));
+        for i in 1..=rows {
+            let mut row = Vec::with_capacity(cols.min(MAX_RESERVED_ELEMENTS));
+            for j in 1..=cols {
+                let value = self.try_expect_type::<U>()
+                    .map_err(|err| err.with_name(&format!("element ({}, {}) of {}", i, j, name)))?;
+                row.push(value);
+            }
+            matrix.push(row);
+        }
+        Ok(matrix)
+    }
+
+    /// Expect the next `rows * cols` tokens from the inner reader can be
+    /// converted to the given type, in row-major order.
+    pub fn expect_matrix<U>(&mut self, rows: usize, cols: usize, name: &str) -> Vec<Vec<U>>
+        where U: FromStr {
+        let result = self.try_expect_matrix(rows, cols, name);
+        self.unwrap_or_fail(result)
+    }
+
+    /// Expect the next `n` tokens from the inner reader form a permutation of
+    /// `1..=n`. Errors name the offending element and, for duplicate values, the
+    /// element where the value first appears.
+    pub fn try_expect_permutation(&mut self, n: usize, name: &str) 
+        -> Result<Vec<usize>, ReadError> {
+        let bound_name = format!("{}[i]", name);
+        let mut first_seen = HashMap::with_capacity(n.min(MAX_RESERVED_ELEMENTS));
+        let mut values = Vec::with_capacity(n.min(MAX_RESERVED_ELEMENTS));
+        for i in 1..=n {
+            let element_name = format!("element {} of {}", i, name);
+            let value = self.try_expect_int_in_range(1, n, &bound_name)
+                .map_err(|err| err.with_name(&element_name))?;
+            if let Some(j) = first_seen.insert(value, i) {
+                return Err(self.error(ReadErrorKind::UnexpectedValue, 
+                    Some(format!("duplicate of element {}", j)), Some(value.to_string()))
+                    .with_name(&element_name));
+            }
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    /// Expect the next `n` tokens from the inner reader form a permutation of
+    /// `1..=n`.
+    pub fn expect_permutation(&mut self, n: usize, name: &str) -> Vec<usize> {
+        let result = self.try_expect_permutation(n, name);
+        self.unwrap_or_fail(result)
+    }
+
+    /// Expect the next `n` tokens from the inner reader can be converted to the
+    /// given type and are pairwise distinct.
+    pub fn try_expect_distinct_vec<U>(&mut self, n: usize, name: &str) 
+        -> Result<Vec<U>, ReadError>
+        where U: FromStr + Display + Hash + Eq + Clone {
+        let mut first_seen = HashMap::with_capacity(n.min(MAX_RESERVED_ELEMENTS));
+        let mut values = Vec::with_capacity(n.min(MAX_RESERVED_ELEMENTS));
+        for i in 1..=n {
+            let element_name = format!("element {} of {}", i, name);
+            let value = self.try_expect_type::<U>()
+                .map_err(|err| err.with_name(&element_name))?;
+            if let Some(j) = first_seen.get(&value) {
+                return Err(self.error(ReadErrorKind::UnexpectedValue, 
+                    Some(format!("duplicate of element {}", j)), Some(value.to_string()))
+                    .with_name(&element_name));
+            }
+            first_seen.insert(value.clone(), i);
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    /// Expect the next `n` tokens from the inner reader can be converted to the
+    /// given type and are pairwise distinct.
+    pub fn expect_distinct_vec<U>(&mut self, n: usize, name: &str) -> Vec<U>
+        where U: FromStr + Display + Hash + Eq + Clone {
+        let result = self.try_expect_distinct_vec(n, name);
+        self.unwrap_or_fail(result)
+    }
+
+    /// Expect the next `n` tokens from the inner reader can be converted to the
+    /// given type and form a non-decreasing sequence.
+    pub fn try_expect_sorted_vec<U>(&mut self, n: usize, name: &str) 
+        -> Result<Vec<U>, ReadError>
+        where U: FromStr + Display + PartialOrd {
+        let mut values: Vec<U> = Vec::with_capacity(n.min(MAX_RESERVED_ELEMENTS));
+        for i in 1..=n {
+            let element_name = format!("element {} of {}", i, name);
+            let value = self.try_expect_type::<U>()
+                .map_err(|err| err.with_name(&element_name))?;
+            // Values that cannot be ordered, e.g. NaN, never form a sorted sequence.
+            let reason = if value.partial_cmp(&value).is_none() {
+                Some(String::from("not comparable with other values"))
+            } else {
+                values.last().and_then(|prev| match value.partial_cmp(prev) {
+                    Some(Ordering::Less) => 
+                        Some(format!("less than element {} = {}", i - 1, prev)),
+                    None => Some(format!("not comparable with element {} = {}", i - 1, prev)),
+                    _ => None
+                })
+            };
+            if let Some(reason) = reason {
+                return Err(self.error(ReadErrorKind::UnexpectedValue, 
+                    Some(reason), Some(value.to_string()))
+                    .with_name(&element_name));
+            }
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    /// Expect the next `n` tokens from the inner reader can be converted to the
+    /// given type and form a non-decreasing sequence.
+    pub fn expect_sorted_vec<U>(&mut self, n: usize, name: &str) -> Vec<U>
+        where U: FromStr + Display + PartialOrd {
+        let result = self.try_expect_sorted_vec(n, name);
+        self.unwrap_or_fail(result)
     }
 
     /// Expect the next token from the inner reader can be converted to the 
     /// given type and equals the given value.
-    pub fn expect_eq<U, V>(&mut self, value: &V) -> U
+    pub fn try_expect_eq<U, V>(&mut self, value: &V) -> Result<U, ReadError>
         where U: FromStr + PartialEq<V> + Display, 
               V: ?Sized + Display {
-        self.expect_value_that(|token_value: &U| if token_value.eq(value) {
+        self.try_expect_value_that(|token_value: &U| if token_value.eq(value) {
             Ok(())
         } else {
             Err(format!("expected value: \"{}\"", value))
         })
     }
 
+    /// Expect the next token from the inner reader can be converted to the 
+    /// given type and equals the given value.
+    pub fn expect_eq<U, V>(&mut self, value: &V) -> U
+        where U: FromStr + PartialEq<V> + Display, 
+              V: ?Sized + Display {
+        let result = self.try_expect_eq(value);
+        self.unwrap_or_fail(result)
+    }
+
     /// Expect the next token from the given reader can be converted to the 
     /// given type but not equals to the given value.
-    pub fn expect_ne<U, V>(&mut self, value: &V) -> U
+    pub fn try_expect_ne<U, V>(&mut self, value: &V) -> Result<U, ReadError>
         where U: FromStr + PartialEq<V> + Display,
               V: ?Sized + Display {
-        self.expect_value_that(|token_value: &U| if token_value.ne(value) {
+        self.try_expect_value_that(|token_value: &U| if token_value.ne(value) {
             Ok(())
         } else {
             Err(())
         })
     }
 
+    /// Expect the next token from the given reader can be converted to the 
+    /// given type but not equals to the given value.
+    pub fn expect_ne<U, V>(&mut self, value: &V) -> U
+        where U: FromStr + PartialEq<V> + Display,
+              V: ?Sized + Display {
+        let result = self.try_expect_ne(value);
+        self.unwrap_or_fail(result)
+    }
+
     /// Expect the next token from the inner reader can be converted to `f64` 
     /// and equals to the given value with an absolute tolerance.
-    pub fn expect_float_eq(&mut self, expected: f64, tolerance: f64) -> f64 {
-        self.expect_value_that(|value: &f64| 
+    pub fn try_expect_float_eq(&mut self, expected: f64, tolerance: f64) 
+        -> Result<f64, ReadError> {
+        self.try_expect_value_that(|value: &f64| 
             match compare_floats(*value, expected, tolerance) {
                 Some(Ordering::Equal) => Ok(()),
                 _ => Err(format!("expected \"{}\", found \"{}\"", 
@@ -178,10 +956,106 @@ impl<T: TokenizedRead> JudgeReader<T> {
             })
     }
 
+    /// Expect the next token from the inner reader can be converted to `f64` 
+    /// and equals to the given value with an absolute tolerance.
+    pub fn expect_float_eq(&mut self, expected: f64, tolerance: f64) -> f64 {
+        let result = self.try_expect_float_eq(expected, tolerance);
+        self.unwrap_or_fail(result)
+    }
+
+    /// Expect the next token from the inner reader can be converted to `f64` 
+    /// and equals to the given value with a relative tolerance.
+    pub fn try_expect_float_eq_rel(&mut self, expected: f64, tolerance: f64) 
+        -> Result<f64, ReadError> {
+        self.try_expect_value_that(|value: &f64| 
+            match compare_floats_rel(*value, expected, tolerance) {
+                Some(Ordering::Equal) => Ok(()),
+                _ => Err(format!("expected \"{}\" with relative error {}, found \"{}\"", 
+                    expected, tolerance, *value))
+            })
+    }
+
+    /// Expect the next token from the inner reader can be converted to `f64` 
+    /// and equals to the given value with a relative tolerance.
+    pub fn expect_float_eq_rel(&mut self, expected: f64, tolerance: f64) -> f64 {
+        let result = self.try_expect_float_eq_rel(expected, tolerance);
+        self.unwrap_or_fail(result)
+    }
+
+    /// Expect the next token from the inner reader can be converted to `f64` 
+    /// and equals to the given value with either an absolute tolerance or a
+    /// relative tolerance.
+    pub fn try_expect_float_eq_abs_or_rel(&mut self, expected: f64, abs_tolerance: f64, 
+        rel_tolerance: f64) -> Result<f64, ReadError> {
+        self.try_expect_value_that(|value: &f64| 
+            match compare_floats_abs_or_rel(*value, expected, abs_tolerance, rel_tolerance) {
+                Some(Ordering::Equal) => Ok(()),
+                _ => Err(format!(
+                    "expected \"{}\" with absolute error {} or relative error {}, found \"{}\"", 
+                    expected, abs_tolerance, rel_tolerance, *value))
+            })
+    }
+
+    /// Expect the next token from the inner reader can be converted to `f64` 
+    /// and equals to the given value with either an absolute tolerance or a
+    /// relative tolerance.
+    pub fn expect_float_eq_abs_or_rel(&mut self, expected: f64, abs_tolerance: f64, 
+        rel_tolerance: f64) -> f64 {
+        let result = self.try_expect_float_eq_abs_or_rel(expected, abs_tolerance, rel_tolerance);
+        self.unwrap_or_fail(result)
+    }
+
+    /// Expect the next token from the inner reader can be converted to `f64` 
+    /// and is at most `max_ulps` representable values away from the given value.
+    pub fn try_expect_float_eq_ulp(&mut self, expected: f64, max_ulps: u64) 
+        -> Result<f64, ReadError> {
+        self.try_expect_value_that(|value: &f64| 
+            match compare_floats_ulp(*value, expected, max_ulps) {
+                Some(Ordering::Equal) => Ok(()),
+                _ => Err(format!("expected \"{}\" within {} ULPs, found \"{}\"", 
+                    expected, max_ulps, *value))
+            })
+    }
+
+    /// Expect the next token from the inner reader can be converted to `f64` 
+    /// and is at most `max_ulps` representable values away from the given value.
+    pub fn expect_float_eq_ulp(&mut self, expected: f64, max_ulps: u64) -> f64 {
+        let result = self.try_expect_float_eq_ulp(expected, max_ulps);
+        self.unwrap_or_fail(result)
+    }
+
+    /// Expect the next token from the inner reader is a decimal number in the
+    /// given syntax that equals to the given value within `10^-precision`. The
+    /// token is compared exactly, without being rounded to `f64`.
+    pub fn try_expect_decimal_eq(&mut self, expected: &Decimal, precision: u32, 
+        syntax: &DecimalSyntax) -> Result<Decimal, ReadError> {
+        let token = self.try_next_token()?;
+        let value = match Decimal::parse(token.as_str(), syntax) {
+            Ok(value) => value,
+            Err(err) => return Err(self.error(ReadErrorKind::InvalidToken, 
+                Some(err.to_string()), Some(token)))
+        };
+        match compare_decimals(&value, expected, precision) {
+            Some(Ordering::Equal) => Ok(value),
+            _ => Err(self.error(ReadErrorKind::UnexpectedValue, 
+                Some(format!("expected \"{}\" with {} decimal digits", expected, precision)), 
+                Some(token)))
+        }
+    }
+
+    /// Expect the next token from the inner reader is a decimal number in the
+    /// given syntax that equals to the given value within `10^-precision`.
+    pub fn expect_decimal_eq(&mut self, expected: &Decimal, precision: u32, 
+        syntax: &DecimalSyntax) -> Decimal {
+        let result = self.try_expect_decimal_eq(expected, precision, syntax);
+        self.unwrap_or_fail(result)
+    }
+
     /// Expect the next token from the inner reader can be converted to `f64` 
     /// but not equals to the given value with an absolute tolerance.
-    pub fn expect_float_ne(&mut self, expected: f64, tolerance: f64) -> f64 {
-        self.expect_value_that(|value: &f64| 
+    pub fn try_expect_float_ne(&mut self, expected: f64, tolerance: f64) 
+        -> Result<f64, ReadError> {
+        self.try_expect_value_that(|value: &f64| 
             match compare_floats(*value, expected, tolerance) {
                 Some(Ordering::Equal) => Err(format!("unexpected value: \"{}\"", 
                     *value)),
@@ -189,20 +1063,456 @@ impl<T: TokenizedRead> JudgeReader<T> {
             })
     }
 
+    /// Expect the next token from the inner reader can be converted to `f64` 
+    /// but not equals to the given value with an absolute tolerance.
+    pub fn expect_float_ne(&mut self, expected: f64, tolerance: f64) -> f64 {
+        let result = self.try_expect_float_ne(expected, tolerance);
+        self.unwrap_or_fail(result)
+    }
+
     /// Expect EOF has been hit on the inner reader.
-    pub fn expect_eof(&mut self) {
-        match self.inner.read_token() {
-            Some(token) => reject!("EOF expected, but found \"{}\"", token),
-            None => ()
+    pub fn try_expect_eof(&mut self) -> Result<(), ReadError> {
+        match self.inner.try_read_token()? {
+            Some(token) => Err(self.error(ReadErrorKind::TrailingData, None, Some(token))),
+            None => Ok(())
         }
     }
+
+    /// Expect EOF has been hit on the inner reader.
+    pub fn expect_eof(&mut self) {
+        let result = self.try_expect_eof();
+        self.unwrap_or_fail(result)
+    }
+}
+
+impl<T: PeekRead> JudgeReader<T> {
+    /// Peek one token from the underlying reader without consuming it.
+    pub fn peek_token(&mut self) -> Option<String> {
+        let result = self.inner.try_peek_token().map_err(ReadError::from);
+        self.unwrap_or_fail(result)
+    }
+
+    /// Peek one line from the underlying reader without consuming it.
+    pub fn peek_line(&mut self) -> Option<String> {
+        let result = self.inner.try_peek_line().map_err(ReadError::from);
+        self.unwrap_or_fail(result)
+    }
+
+    /// Determine whether there are more tokens available in the underlying
+    /// reader, without consuming anything.
+    pub fn has_next_token(&mut self) -> bool {
+        self.inner.has_next_token()
+    }
+
+    /// Determine whether only whitespace characters are left in the underlying
+    /// reader, without consuming anything.
+    pub fn seek_eof(&mut self) -> bool {
+        self.inner.seek_eof()
+    }
+}
+
+impl<R: Read> JudgeReader<StrictReader<R>> {
+    /// Expect the next byte from the inner reader to be a single space.
+    pub fn try_expect_space(&mut self) -> Result<(), ReadError> {
+        Ok(self.inner.expect_space()?)
+    }
+
+    /// Expect the next byte from the inner reader to be a single space.
+    pub fn expect_space(&mut self) {
+        let result = self.try_expect_space();
+        self.unwrap_or_fail(result)
+    }
+
+    /// Expect the next byte from the inner reader to be a `\n`.
+    pub fn try_expect_eoln(&mut self) -> Result<(), ReadError> {
+        Ok(self.inner.expect_eoln()?)
+    }
+
+    /// Expect the next byte from the inner reader to be a `\n`.
+    pub fn expect_eoln(&mut self) {
+        let result = self.try_expect_eoln();
+        self.unwrap_or_fail(result)
+    }
+
+    /// Expect EOF has been hit exactly on the inner reader, i.e. there are no
+    /// more bytes left, not even whitespace characters.
+    pub fn try_expect_eof_exact(&mut self) -> Result<(), ReadError> {
+        Ok(self.inner.expect_eof_exact()?)
+    }
+
+    /// Expect EOF has been hit exactly on the inner reader, i.e. there are no
+    /// more bytes left, not even whitespace characters.
+    pub fn expect_eof_exact(&mut self) {
+        let result = self.try_expect_eof_exact();
+        self.unwrap_or_fail(result)
+    }
 }
 
 
 pub mod preclude {
     pub use super::EXIT_ACCEPTED;
     pub use super::EXIT_REJECTED;
+    pub use super::EXIT_JUDGE_FAILURE;
+    pub use super::EXIT_PRESENTATION_ERROR;
+    pub use super::EXIT_PARTIAL_SCORE;
+    pub use super::EXIT_INVALID_INPUT;
+    pub use super::Verdict;
+    pub use super::quit;
     pub use super::accept;
     pub use super::reject;
+    pub use super::presentation_error;
+    pub use super::partial;
+    pub use super::partial_points;
+    pub use super::invalid_input;
+    pub use super::judge_fail;
+    pub use super::feedback;
+    pub use super::judge_log;
+    pub use super::{ReadError, ReadErrorKind};
     pub use super::JudgeReader;
 }
+
+
+#[cfg(test)]
+mod tests {
+    use crate::strict::StrictReader;
+    use crate::tokenized::TokenizedReader;
+
+    use crate::cmp::DecimalSyntax;
+    use crate::tokenized::NumberFormat;
+
+    use super::{JudgeReader, ReadErrorKind, Verdict};
+
+    /// Environment variable that selects the case run by [`test_exit_codes`] in
+    /// a child process.
+    const ENV_EXIT_CASE: &str = "WAVE_TEST_EXIT_CASE";
+
+    /// Run the given case of [`test_exit_codes`] in a child process and return
+    /// the low byte of its exit code, along with the content of the JSON result
+    /// file and the judge log file written by the child.
+    fn run_exit_case(case: &str) -> (i32, String, String) {
+        let result_file = std::env::temp_dir().join(
+            format!("wave_test_lib_{}_exit_{}.json", std::process::id(), case));
+        let judge_log_file = std::env::temp_dir().join(
+            format!("wave_test_lib_{}_exit_{}.log", std::process::id(), case));
+        let status = std::process::Command::new(std::env::current_exe().unwrap())
+            .args(["contract::tests::test_exit_codes", "--exact", "--test-threads=1"])
+            .env(ENV_EXIT_CASE, case)
+            .env(crate::report::ENV_RESULT_FILE, &result_file)
+            .env(crate::report::ENV_JUDGE_LOG, &judge_log_file)
+            .env_remove(crate::report::ENV_RESULT_FORMAT)
+            .env_remove(crate::report::ENV_PROTOCOL)
+            .output()
+            .unwrap()
+            .status;
+        let result = std::fs::read_to_string(&result_file).unwrap_or_default();
+        let judge_log = std::fs::read_to_string(&judge_log_file).unwrap_or_default();
+        let _ = std::fs::remove_file(&result_file);
+        let _ = std::fs::remove_file(&judge_log_file);
+        (status.code().unwrap() & 0xff, result, judge_log)
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        if let Ok(case) = std::env::var(ENV_EXIT_CASE) {
+            crate::report::set_result_file(crate::report::ResultFile::from_env().unwrap());
+            crate::report::set_judge_log_file(
+                std::env::var_os(crate::report::ENV_JUDGE_LOG).map(std::path::PathBuf::from));
+            match case.as_str() {
+                "quit" => super::quit(Verdict::PresentationError, None),
+                "partial" => super::partial(0.5, None),
+                "partial_out_of_range" => super::partial(1.5, None),
+                "partial_nan" => super::partial(f64::NAN, None),
+                "partial_points" => super::partial_points(3.0, 6.0, None),
+                "partial_points_out_of_range" => super::partial_points(7.0, 6.0, None),
+                "partial_points_zero_max" => super::partial_points(0.0, 0.0, None),
+                "judge_log" => {
+                    super::judge_log("expected 3, found 4");
+                    super::reject("wrong")
+                },
+                "graph_check" => {
+                    let reader = JudgeReader::with_failure_verdict(
+                        TokenizedReader::new(&b""[..]), Verdict::JudgeFailure);
+                    reader.expect_satisfied(crate::graph::Graph::new(2).is_connected());
+                    panic!("graph check passed")
+                },
+                _ => panic!("unknown exit case: {}", case)
+            }
+        }
+
+        let cases = [
+            ("quit", Verdict::PresentationError),
+            ("partial", Verdict::PartialScore),
+            ("partial_out_of_range", Verdict::JudgeFailure),
+            ("partial_nan", Verdict::JudgeFailure),
+            ("partial_points", Verdict::PartialScore),
+            ("partial_points_out_of_range", Verdict::JudgeFailure),
+            ("partial_points_zero_max", Verdict::JudgeFailure),
+            ("judge_log", Verdict::WrongAnswer),
+            ("graph_check", Verdict::JudgeFailure)
+        ];
+        for &(case, verdict) in &cases {
+            let (code, _, _) = run_exit_case(case);
+            assert_eq!(code, verdict.exit_code() & 0xff, "exit case {}", case);
+        }
+
+        let (_, result, judge_log) = run_exit_case("partial_points");
+        assert!(result.contains("\"score\":0.5,\"points\":3,\"max_points\":6"), 
+            "unexpected result file: {}", result);
+        assert_eq!(judge_log, "");
+
+        let (_, _, judge_log) = run_exit_case("judge_log");
+        assert_eq!(judge_log, "expected 3, found 4\n");
+    }
+
+    fn create_test_reader(content: &'static str) -> JudgeReader<TokenizedReader<&'static [u8]>> {
+        JudgeReader::new(TokenizedReader::new(content.as_bytes()))
+    }
+
+    #[test]
+    fn test_try_expect_type() {
+        let mut reader = create_test_reader("12 abc");
+        assert_eq!(reader.try_expect_type::<i32>(), Ok(12));
+
+        let err = reader.try_expect_type::<i32>().unwrap_err();
+        assert_eq!(err.kind, ReadErrorKind::InvalidToken);
+        assert_eq!(err.found.as_deref(), Some("abc"));
+
+        let err = reader.try_expect_type::<i32>().unwrap_err();
+        assert_eq!(err.kind, ReadErrorKind::UnexpectedEof);
+        assert_eq!(err.to_string(), "Unexpected EOF (line 1, column 7, token 3)");
+    }
+
+    #[test]
+    fn test_try_expect_token() {
+        let mut reader = create_test_reader("YES no");
+        assert_eq!(reader.try_expect_token("yes", true), Ok(String::from("YES")));
+
+        let err = reader.try_expect_token("NO", false).unwrap_err();
+        assert_eq!(err.kind, ReadErrorKind::TokenMismatch);
+        assert_eq!(err.expected.as_deref(), Some("NO"));
+        assert_eq!(err.found.as_deref(), Some("no"));
+        assert_eq!(err.to_string(), 
+            "expect \"NO\", found \"no\" (line 1, column 5, token 2)");
+    }
+
+    #[test]
+    fn test_try_expect_value() {
+        let mut reader = create_test_reader("3 4 1.5 2.0");
+        assert_eq!(reader.try_expect_eq::<i32, i32>(&3), Ok(3));
+        assert_eq!(reader.try_expect_eq::<i32, i32>(&3).unwrap_err().kind, 
+            ReadErrorKind::UnexpectedValue);
+        assert_eq!(reader.try_expect_float_eq(1.5 + 1e-9, 1e-6), Ok(1.5));
+        assert_eq!(reader.try_expect_float_ne(2.0, 1e-6).unwrap_err().found.as_deref(), 
+            Some("2"));
+    }
+
+    #[test]
+    fn test_try_expect_in_range() {
+        let mut reader = create_test_reader("5 100001 x 0.5 nan");
+        assert_eq!(reader.try_expect_int_in_range(1, 100000, "n"), Ok(5));
+
+        let err = reader.try_expect_int_in_range(1, 100000, "n").unwrap_err();
+        assert_eq!(err.kind, ReadErrorKind::OutOfRange);
+        assert_eq!(err.to_string(), 
+            "n = 100001 violates 1 \u{2264} n \u{2264} 100000 (line 1, column 3, token 2)");
+
+        let err = reader.try_expect_int_in_range(1i64, 10, "m").unwrap_err();
+        assert_eq!(err.kind, ReadErrorKind::InvalidToken);
+        assert_eq!(err.to_string(), 
+            "Unexpected token: \"x\" while reading m (line 1, column 10, token 3)");
+
+        assert_eq!(reader.try_expect_float_in_range(0.0, 1.0, "p"), Ok(0.5));
+        let err = reader.try_expect_float_in_range(0.0, 1.0, "p").unwrap_err();
+        assert_eq!(err.kind, ReadErrorKind::OutOfRange);
+    }
+
+    #[test]
+    fn test_try_expect_vec() {
+        let mut reader = create_test_reader("1 2 3\n4 5 x");
+        assert_eq!(reader.try_expect_vec::<i32>(3, "a"), Ok(vec![1, 2, 3]));
+        let err = reader.try_expect_vec::<i32>(3, "b").unwrap_err();
+        assert_eq!(err.kind, ReadErrorKind::InvalidToken);
+        assert_eq!(err.to_string(), 
+            "Unexpected token: \"x\" while reading element 3 of b (line 2, column 5, token 6)");
+
+        let mut reader = create_test_reader("1 2");
+        let err = reader.try_expect_vec::<i32>(3, "a").unwrap_err();
+        assert_eq!(err.kind, ReadErrorKind::UnexpectedEof);
+        assert_eq!(err.name.as_deref(), Some("element 3 of a"));
+
+        // A huge count printed by the contestant must not be reserved up front.
+        let mut reader = create_test_reader("1 2");
+        let err = reader.try_expect_vec::<i32>(usize::MAX, "a").unwrap_err();
+        assert_eq!(err.kind, ReadErrorKind::UnexpectedEof);
+        let mut reader = create_test_reader("1 2");
+        let err = reader.try_expect_matrix::<i32>(usize::MAX, usize::MAX, "a").unwrap_err();
+        assert_eq!(err.kind, ReadErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_try_expect_vec_in_range() {
+        let mut reader = create_test_reader("1 5 10 11");
+        assert_eq!(reader.try_expect_vec_in_range(3, 1, 10, "a"), Ok(vec![1, 5, 10]));
+
+        let mut reader = create_test_reader("1 5 10 11");
+        let err = reader.try_expect_vec_in_range(4, 1, 10, "a").unwrap_err();
+        assert_eq!(err.kind, ReadErrorKind::OutOfRange);
+        assert_eq!(err.to_string(), 
+            "element 4 of a = 11 violates 1 \u{2264} a[i] \u{2264} 10 (line 1, column 8, token 4)");
+    }
+
+    #[test]
+    fn test_try_expect_matrix() {
+        let mut reader = create_test_reader("1 2\n3 4\n5 y\n");
+        assert_eq!(reader.try_expect_matrix::<i32>(2, 2, "g"), Ok(vec![vec![1, 2], vec![3, 4]]));
+        let err = reader.try_expect_matrix::<i32>(1, 2, "h").unwrap_err();
+        assert_eq!(err.name.as_deref(), Some("element (1, 2) of h"));
+    }
+
+    #[test]
+    fn test_try_expect_permutation() {
+        let mut reader = create_test_reader("3 1 2\n2 3 2\n1 4\n");
+        assert_eq!(reader.try_expect_permutation(3, "p"), Ok(vec![3, 1, 2]));
+
+        let err = reader.try_expect_permutation(3, "q").unwrap_err();
+        assert_eq!(err.kind, ReadErrorKind::UnexpectedValue);
+        assert_eq!(err.to_string(), "Unexpected value: \"2\": duplicate of element 1 \
+            while reading element 3 of q (line 2, column 5, token 6)");
+
+        let err = reader.try_expect_permutation(2, "r").unwrap_err();
+        assert_eq!(err.kind, ReadErrorKind::OutOfRange);
+        assert_eq!(err.name.as_deref(), Some("element 2 of r"));
+
+        let mut reader = create_test_reader("1 2");
+        let err = reader.try_expect_permutation(usize::MAX, "p").unwrap_err();
+        assert_eq!(err.kind, ReadErrorKind::UnexpectedEof);
+        let mut reader = create_test_reader("1 2");
+        let err = reader.try_expect_distinct_vec::<i32>(usize::MAX, "d").unwrap_err();
+        assert_eq!(err.kind, ReadErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_try_expect_distinct_vec() {
+        let mut reader = create_test_reader("a b c\n5 7 9 7");
+        assert_eq!(reader.try_expect_distinct_vec::<String>(3, "s").unwrap().len(), 3);
+
+        let err = reader.try_expect_distinct_vec::<i64>(4, "a").unwrap_err();
+        assert_eq!(err.to_string(), "Unexpected value: \"7\": duplicate of element 2 \
+            while reading element 4 of a (line 2, column 7, token 7)");
+    }
+
+    #[test]
+    fn test_try_expect_sorted_vec() {
+        let mut reader = create_test_reader("1 1 2 3\n1 5 4");
+        assert_eq!(reader.try_expect_sorted_vec(4, "a"), Ok(vec![1, 1, 2, 3]));
+
+        let err = reader.try_expect_sorted_vec::<i32>(3, "b").unwrap_err();
+        assert_eq!(err.to_string(), "Unexpected value: \"4\": less than element 2 = 5 \
+            while reading element 3 of b (line 2, column 5, token 7)");
+
+        let mut reader = create_test_reader("1 NaN\nNaN");
+        let err = reader.try_expect_sorted_vec::<f64>(2, "c").unwrap_err();
+        assert_eq!(err.to_string(), "Unexpected value: \"NaN\": not comparable with other \
+            values while reading element 2 of c (line 1, column 3, token 2)");
+        assert!(reader.try_expect_sorted_vec::<f64>(1, "d").is_err());
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let mut reader = JudgeReader::new(TokenizedReader::new(b"1 \xff2 3\x80".as_ref()));
+        assert_eq!(reader.try_expect_token("1", false), Ok(String::from("1")));
+        let err = reader.try_expect_token("2", false).unwrap_err();
+        assert_eq!(err.kind, ReadErrorKind::InvalidUtf8);
+        assert_eq!(err.to_string(), 
+            "Invalid UTF-8: byte 0xff at offset 2 (line 1, column 3, token 2)");
+        let err = reader.try_expect_type::<String>().unwrap_err();
+        assert_eq!(err.to_string(), 
+            "Invalid UTF-8: byte 0x80 at offset 6 (line 1, column 7, token 3)");
+    }
+
+    #[test]
+    fn test_read_line_tokens() {
+        let mut reader = create_test_reader("1  2 3\n\nabc\n");
+        assert_eq!(reader.read_line_tokens(), 
+            Some(vec![String::from("1"), String::from("2"), String::from("3")]));
+        assert_eq!(reader.read_line_tokens(), Some(vec![String::from("abc")]));
+        assert_eq!(reader.read_line_tokens(), None);
+    }
+
+    #[test]
+    fn test_peek() {
+        let mut reader = create_test_reader("-1\n3 1 2\n  \n");
+        assert_eq!(reader.peek_token(), Some(String::from("-1")));
+        assert_eq!(reader.try_expect_i64(), Ok(-1));
+        assert_eq!(reader.peek_line(), Some(String::from("3 1 2")));
+        assert_eq!(reader.read_line_tokens(), 
+            Some(vec![String::from("3"), String::from("1"), String::from("2")]));
+        assert!(!reader.has_next_token());
+        assert!(reader.seek_eof());
+    }
+
+    #[test]
+    fn test_try_expect_float_eq_variants() {
+        let mut reader = create_test_reader("1000000001 1000010000 0.0000001 0.1 1.0000000000000002");
+        assert_eq!(reader.try_expect_float_eq_rel(1e9, 1e-6), Ok(1000000001.0));
+        assert_eq!(reader.try_expect_float_eq_rel(1e9, 1e-6).unwrap_err().kind, 
+            ReadErrorKind::UnexpectedValue);
+        assert_eq!(reader.try_expect_float_eq_abs_or_rel(0.0, 1e-6, 1e-6), Ok(1e-7));
+        assert!(reader.try_expect_float_eq_abs_or_rel(0.2, 1e-6, 1e-6).is_err());
+        assert_eq!(reader.try_expect_float_eq_ulp(1.0, 1), Ok(1.0000000000000002));
+    }
+
+    #[test]
+    fn test_try_expect_decimal_eq() {
+        let mut reader = create_test_reader("0.30000000000000004 +0.3 0.31");
+        let expected = "0.3".parse().unwrap();
+        let strict = DecimalSyntax::strict();
+        assert!(reader.try_expect_decimal_eq(&expected, 16, &strict).is_ok());
+
+        let err = reader.try_expect_decimal_eq(&expected, 16, &strict).unwrap_err();
+        assert_eq!(err.to_string(), 
+            "Unexpected token: \"+0.3\": leading plus sign is not allowed (line 1, column 21, token 2)");
+
+        let err = reader.try_expect_decimal_eq(&expected, 3, &strict).unwrap_err();
+        assert_eq!(err.to_string(), "Unexpected value: \"0.31\": expected \"0.3\" \
+            with 3 decimal digits (line 1, column 26, token 3)");
+    }
+
+    #[test]
+    fn test_try_expect_numbers() {
+        let mut reader = create_test_reader("-5 007 99999999999999999999 1.5 x");
+        assert_eq!(reader.try_expect_i64(), Ok(-5));
+        reader.set_number_format(NumberFormat::strict());
+        let err = reader.try_expect_u64().unwrap_err();
+        assert_eq!(err.to_string(), 
+            "Unexpected token: \"007\": leading zeros are not allowed (line 1, column 4, token 2)");
+        let err = reader.try_expect_i64().unwrap_err();
+        assert_eq!(err.expected.as_deref(), Some("integer overflow"));
+        assert_eq!(reader.try_expect_f64(), Ok(1.5));
+        assert_eq!(reader.try_expect_f64().unwrap_err().kind, ReadErrorKind::InvalidToken);
+        assert_eq!(reader.try_expect_f64().unwrap_err().kind, ReadErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_try_expect_eof() {
+        let mut reader = create_test_reader("1\n");
+        let err = reader.try_expect_eof().unwrap_err();
+        assert_eq!(err.kind, ReadErrorKind::TrailingData);
+        assert_eq!(reader.try_expect_eof(), Ok(()));
+    }
+
+    #[test]
+    fn test_strict_judge_reader() {
+        let mut reader = JudgeReader::new(StrictReader::new("1 2\n".as_bytes()));
+        assert_eq!(reader.try_expect_type::<i32>(), Ok(1));
+        let err = reader.try_expect_type::<i32>().unwrap_err();
+        assert_eq!(err.kind, ReadErrorKind::InvalidFormat);
+        assert_eq!(err.to_string(), 
+            "Invalid format: expected token, found space (line 1, column 2, token 2)");
+
+        reader.try_expect_space().unwrap();
+        assert_eq!(reader.try_expect_type::<i32>(), Ok(2));
+        reader.try_expect_eoln().unwrap();
+        reader.try_expect_eof_exact().unwrap();
+    }
+}
@@ -1,17 +1,34 @@
+#![allow(clippy::missing_safety_doc)]
+
 use std::cmp::Ordering;
 use std::ffi::{c_void, CStr};
 use std::os::raw::c_char;
 use std::boxed::Box;
+use std::cell::RefCell;
+use std::convert::TryFrom;
+use std::fmt::Display;
 
-use crate::{Checker, Interactor};
-use crate::tokenized::TokenizedRead;
-use crate::cmp::compare_floats;
+use crate::{Checker, Interactor, JudgeReader, Validator};
+use crate::contract::{quit, ReadError, ReadErrorKind};
+use crate::cmp::{compare_floats, compare_floats_rel, compare_floats_abs_or_rel};
+use crate::bigint::BigInt;
+use crate::cmp::compare_floats_ulp;
+use crate::cmp::{compare_decimals, Decimal, DecimalSyntax};
+use crate::tokenized::NumberFormat;
 
 
 mod convert {
     use std::ffi::c_void;
 
-    use crate::{Checker, Interactor, JudgeReader};
+    use crate::{Checker, Interactor, JudgeReader, Validator};
+    use crate::contract::Verdict;
+
+    pub const VERDICT_ACCEPTED: i32 = 0;
+    pub const VERDICT_WRONG_ANSWER: i32 = 1;
+    pub const VERDICT_PRESENTATION_ERROR: i32 = 2;
+    pub const VERDICT_PARTIAL_SCORE: i32 = 3;
+    pub const VERDICT_JUDGE_FAILURE: i32 = 4;
+    pub const VERDICT_INVALID_INPUT: i32 = 5;
 
     pub unsafe fn to_raw<T>(value: &mut T) -> *mut c_void {
         value as *mut T
@@ -26,212 +43,474 @@ mod convert {
         (raw as *mut Interactor).as_mut().unwrap()
     }
 
+    pub unsafe fn to_validator(raw: *mut c_void) -> &'static mut Validator {
+        (raw as *mut Validator).as_mut().unwrap()
+    }
+
     pub unsafe fn to_reader(raw: *mut c_void) -> &'static mut JudgeReader {
         (raw as *mut JudgeReader).as_mut().unwrap()
     }
+
+    pub fn to_verdict(code: i32) -> Verdict {
+        match code {
+            VERDICT_ACCEPTED => Verdict::Accepted,
+            VERDICT_WRONG_ANSWER => Verdict::WrongAnswer,
+            VERDICT_PRESENTATION_ERROR => Verdict::PresentationError,
+            VERDICT_PARTIAL_SCORE => Verdict::PartialScore,
+            VERDICT_JUDGE_FAILURE => Verdict::JudgeFailure,
+            VERDICT_INVALID_INPUT => Verdict::InvalidInput,
+            _ => judge_fail!("unknown verdict code: {}", code)
+        }
+    }
 }
 
 use convert::*;
 
 
 #[no_mangle]
-pub unsafe extern fn wave_accept() {
+pub unsafe extern "C" fn wave_accept() {
     accept!();
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_accept_msg(msg: *const c_char) {
+pub unsafe extern "C" fn wave_accept_msg(msg: *const c_char) {
     accept!("{}", CStr::from_ptr(msg).to_str().unwrap());
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_reject(msg: *const c_char) {
+pub unsafe extern "C" fn wave_reject(msg: *const c_char) {
     reject!("{}", CStr::from_ptr(msg).to_str().unwrap());
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn wave_presentation_error(msg: *const c_char) {
+    presentation_error!("{}", CStr::from_ptr(msg).to_str().unwrap());
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_partial(score: f64, msg: *const c_char) {
+    if msg.is_null() {
+        partial!(score);
+    }
+    partial!(score, "{}", CStr::from_ptr(msg).to_str().unwrap());
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_partial_points(
+    points: f64, max_points: f64, msg: *const c_char) {
+    let msg = if msg.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(msg).to_str().unwrap())
+    };
+    crate::contract::partial_points(points, max_points, msg);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_judge_fail(msg: *const c_char) {
+    judge_fail!("{}", CStr::from_ptr(msg).to_str().unwrap());
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_feedback(msg: *const c_char) {
+    feedback!("{}", CStr::from_ptr(msg).to_str().unwrap());
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_judge_log(msg: *const c_char) {
+    judge_log!("{}", CStr::from_ptr(msg).to_str().unwrap());
+}
 
 #[no_mangle]
-pub unsafe extern fn wave_checker_create() -> *mut c_void {
+pub unsafe extern "C" fn wave_quit(verdict: i32, msg: *const c_char) {
+    let verdict = to_verdict(verdict);
+    if msg.is_null() {
+        quit!(verdict);
+    }
+    quit!(verdict, "{}", CStr::from_ptr(msg).to_str().unwrap());
+}
+
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_checker_create() -> *mut c_void {
     let checker = Box::new(Checker::new());
     Box::into_raw(checker) as *mut c_void
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_checker_release(checker: *mut c_void) {
+pub unsafe extern "C" fn wave_checker_release(checker: *mut c_void) {
     let checker = Box::from_raw(checker as *mut Checker);
     drop(checker);
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_checker_get_input_handle(checker: *mut c_void) 
+pub unsafe extern "C" fn wave_checker_get_input_handle(checker: *mut c_void) 
     -> *mut c_void {
     to_raw(to_checker(checker).input())
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_checker_get_std_answer_handle(checker: *mut c_void) 
+pub unsafe extern "C" fn wave_checker_get_std_answer_handle(checker: *mut c_void) 
     -> *mut c_void {
     to_raw(to_checker(checker).std_answer())
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_checker_get_user_answer_handle(checker: *mut c_void) 
+pub unsafe extern "C" fn wave_checker_get_user_answer_handle(checker: *mut c_void) 
     -> *mut c_void {
     to_raw(to_checker(checker).user_answer())
 }
 
 
 #[no_mangle]
-pub unsafe extern fn wave_interactor_create() -> *mut c_void {
+pub unsafe extern "C" fn wave_interactor_create() -> *mut c_void {
     let interactor = Box::new(Interactor::new());
     Box::into_raw(interactor) as *mut c_void
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_interactor_release(interactor: *mut c_void) {
+pub unsafe extern "C" fn wave_interactor_release(interactor: *mut c_void) {
     let interactor = Box::from_raw(interactor as *mut Interactor);
     drop(interactor);
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_interactor_get_input_handle(interactor: *mut c_void) 
+pub unsafe extern "C" fn wave_interactor_get_input_handle(interactor: *mut c_void) 
     -> *mut c_void {
     to_raw(to_interactor(interactor).input())
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_interactor_get_answer_handle(interactor: *mut c_void) 
+pub unsafe extern "C" fn wave_interactor_get_answer_handle(interactor: *mut c_void) 
     -> *mut c_void {
     to_raw(to_interactor(interactor).answer())
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_interactor_get_read_end_handle(
+pub unsafe extern "C" fn wave_interactor_get_read_end_handle(
     interactor: *mut c_void) -> *mut c_void {
     to_raw(to_interactor(interactor).read_end())
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_interactor_get_write_end_handle(
+pub unsafe extern "C" fn wave_interactor_get_write_end_handle(
     interactor: *mut c_void) -> *mut c_void {
     to_raw(to_interactor(interactor).write_end())
 }
 
 
-static mut LAST_READ: Option<String> = None;
+#[no_mangle]
+pub unsafe extern "C" fn wave_validator_create() -> *mut c_void {
+    let validator = Box::new(Validator::new());
+    Box::into_raw(validator) as *mut c_void
+}
 
-unsafe fn try_copy_str(s: &str, buffer: *mut c_void, buffer_size: usize) 
-    -> usize {
-    let len = s.len() + 1;
-    if len > buffer_size {
-        // Buffer is too small to fit the token.
-        return len;
-    }
+#[no_mangle]
+pub unsafe extern "C" fn wave_validator_release(validator: *mut c_void) {
+    let validator = Box::from_raw(validator as *mut Validator);
+    drop(validator);
+}
 
-    let mut buffer = buffer as *mut u8;
-    let bytes = LAST_READ.take().unwrap().into_bytes();
-    for b in bytes {
-        *buffer = b;
-        buffer = buffer.add(1);
-    }
+#[no_mangle]
+pub unsafe extern "C" fn wave_validator_read_i64_in_range(
+    validator: *mut c_void, lo: i64, hi: i64, name: *const c_char) -> i64 {
+    to_validator(validator).read_int_in_range(
+        lo, hi, CStr::from_ptr(name).to_str().unwrap())
+}
 
-    *buffer = 0;
-    len + 1
+#[no_mangle]
+pub unsafe extern "C" fn wave_validator_read_u64_in_range(
+    validator: *mut c_void, lo: u64, hi: u64, name: *const c_char) -> u64 {
+    to_validator(validator).read_int_in_range(
+        lo, hi, CStr::from_ptr(name).to_str().unwrap())
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_read_token(
-    handle: *mut c_void, buffer: *mut c_void, buffer_size: usize) -> usize {
-    let reader = to_reader(handle);
-    if LAST_READ.is_none() {
-        LAST_READ = reader.inner_reader().read_token();
-        if LAST_READ.is_none() {
-            return 0;
+pub unsafe extern "C" fn wave_validator_read_token(
+    validator: *mut c_void, buffer: *mut c_void, buffer_size: usize) -> usize {
+    let reader = to_validator(validator).reader();
+    fill_last_read(|| Some(reader.expect_type::<String>()));
+    try_copy_last_read(buffer, buffer_size)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_validator_expect_space(validator: *mut c_void) {
+    to_validator(validator).reader().expect_space();
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_validator_expect_eoln(validator: *mut c_void) {
+    to_validator(validator).reader().expect_eoln();
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_validator_finish(validator: *mut c_void) {
+    to_validator(validator).finish();
+}
+
+
+thread_local! {
+    static LAST_READ: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Copy the pending string in `LAST_READ` into the given buffer. If the buffer
+/// is too small to fit the string, the string is kept so that it can be
+/// retrieved by the next call with a larger buffer. Returns the number of bytes
+/// required to hold the string, including the trailing NUL character.
+unsafe fn try_copy_last_read(buffer: *mut c_void, buffer_size: usize) -> usize {
+    LAST_READ.with(|last_read| {
+        let mut last_read = last_read.borrow_mut();
+        let len = match last_read.as_ref() {
+            Some(s) => s.len() + 1,
+            None => return 0
+        };
+        if len > buffer_size {
+            // Buffer is too small to fit the token.
+            return len;
         }
-    }
 
-    try_copy_str(LAST_READ.take().unwrap().as_str(), buffer, buffer_size)    
+        let bytes = last_read.take().unwrap().into_bytes();
+        let buffer = buffer as *mut u8;
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, bytes.len());
+        *buffer.add(bytes.len()) = 0;
+        len
+    })
+}
+
+/// Fill `LAST_READ` with the given reading function if it is empty.
+fn fill_last_read<F>(read: F)
+    where F: FnOnce() -> Option<String> {
+    LAST_READ.with(|last_read| {
+        let mut last_read = last_read.borrow_mut();
+        if last_read.is_none() {
+            *last_read = read();
+        }
+    })
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_read_line(
+pub unsafe extern "C" fn wave_read_token(
     handle: *mut c_void, buffer: *mut c_void, buffer_size: usize) -> usize {
     let reader = to_reader(handle);
-    if LAST_READ.is_none() {
-        LAST_READ = reader.inner_reader().read_line();
-        if LAST_READ.is_none() {
-            return 0;
-        }
-    }
+    fill_last_read(|| reader.read_token());
+    try_copy_last_read(buffer, buffer_size)
+}
 
-    try_copy_str(LAST_READ.take().unwrap().as_str(), buffer, buffer_size)
+#[no_mangle]
+pub unsafe extern "C" fn wave_read_line(
+    handle: *mut c_void, buffer: *mut c_void, buffer_size: usize) -> usize {
+    let reader = to_reader(handle);
+    fill_last_read(|| reader.read_line());
+    try_copy_last_read(buffer, buffer_size)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_set_number_format(
+    handle: *mut c_void, allow_plus_sign: i32, allow_leading_zeros: i32) {
+    to_reader(handle).set_number_format(NumberFormat {
+        allow_plus_sign: allow_plus_sign != 0,
+        allow_leading_zeros: allow_leading_zeros != 0
+    });
+}
+
+/// Read an integer with the given numeric read on the reader and narrow it to
+/// the given type, exiting the program with the failure verdict of the reader if
+/// the integer cannot be read or does not fit.
+fn expect_narrow<W, U, F>(reader: &mut JudgeReader, read: F) -> U
+    where W: Copy + Display,
+          U: TryFrom<W>,
+          F: FnOnce(&mut JudgeReader) -> Result<W, ReadError> {
+    let result = read(reader).and_then(|value| U::try_from(value).map_err(|_| 
+        ReadError::new(ReadErrorKind::InvalidToken, Some(String::from("integer overflow")), 
+            Some(value.to_string()))
+            .with_position(reader.position())));
+    match result {
+        Ok(value) => value,
+        Err(err) => quit(reader.failure_verdict(), Some(err.to_string().as_str()))
+    }
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_expect_i8(handle: *mut c_void) -> i8 {
-    to_reader(handle).expect_type::<i8>()
+pub unsafe extern "C" fn wave_expect_i8(handle: *mut c_void) -> i8 {
+    expect_narrow(to_reader(handle), JudgeReader::try_expect_i64)
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_expect_u8(handle: *mut c_void) -> u8 {
-    to_reader(handle).expect_type::<u8>()
+pub unsafe extern "C" fn wave_expect_u8(handle: *mut c_void) -> u8 {
+    expect_narrow(to_reader(handle), JudgeReader::try_expect_u64)
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_expect_i16(handle: *mut c_void) -> i16 {
-    to_reader(handle).expect_type::<i16>()
+pub unsafe extern "C" fn wave_expect_i16(handle: *mut c_void) -> i16 {
+    expect_narrow(to_reader(handle), JudgeReader::try_expect_i64)
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_expect_u16(handle: *mut c_void) -> u16 {
-    to_reader(handle).expect_type::<u16>()
+pub unsafe extern "C" fn wave_expect_u16(handle: *mut c_void) -> u16 {
+    expect_narrow(to_reader(handle), JudgeReader::try_expect_u64)
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_expect_i32(handle: *mut c_void) -> i32 {
-    to_reader(handle).expect_type::<i32>()
+pub unsafe extern "C" fn wave_expect_i32(handle: *mut c_void) -> i32 {
+    expect_narrow(to_reader(handle), JudgeReader::try_expect_i64)
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_expect_u32(handle: *mut c_void) -> u32 {
-    to_reader(handle).expect_type::<u32>()
+pub unsafe extern "C" fn wave_expect_u32(handle: *mut c_void) -> u32 {
+    expect_narrow(to_reader(handle), JudgeReader::try_expect_u64)
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_expect_i64(handle: *mut c_void) -> i64 {
-    to_reader(handle).expect_type::<i64>()
+pub unsafe extern "C" fn wave_expect_i64(handle: *mut c_void) -> i64 {
+    to_reader(handle).expect_i64()
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_expect_u64(handle: *mut c_void) -> u64 {
-    to_reader(handle).expect_type::<u64>()
+pub unsafe extern "C" fn wave_expect_u64(handle: *mut c_void) -> u64 {
+    to_reader(handle).expect_u64()
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_expect_token(
+pub unsafe extern "C" fn wave_expect_token(
     handle: *mut c_void, expected: *mut c_char, ignore_case: i32) {
     to_reader(handle).expect_token(
         CStr::from_ptr(expected).to_str().unwrap(), ignore_case != 0);
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_expect_signed(handle: *mut c_void, expected: i64) {
+pub unsafe extern "C" fn wave_expect_signed(handle: *mut c_void, expected: i64) {
     to_reader(handle).expect_eq::<i64, i64>(&expected);
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_expect_unsigned(handle: *mut c_void, expected: u64) {
+pub unsafe extern "C" fn wave_expect_unsigned(handle: *mut c_void, expected: u64) {
     to_reader(handle).expect_eq::<u64, u64>(&expected);
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_expect_fp(
+pub unsafe extern "C" fn wave_expect_fp(
     handle: *mut c_void, expected: f64, tolerance: f64) {
     to_reader(handle).expect_float_eq(expected, tolerance);
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_expect_eof(handle: *mut c_void) {
+pub unsafe extern "C" fn wave_expect_decimal(
+    handle: *mut c_void, expected: *const c_char, precision: u32, strict: i32) {
+    let expected = match Decimal::parse(CStr::from_ptr(expected).to_str().unwrap(), 
+        &DecimalSyntax::default()) {
+        Ok(expected) => expected,
+        Err(err) => judge_fail!("invalid expected decimal: {}", err)
+    };
+    to_reader(handle).expect_decimal_eq(&expected, precision, &to_decimal_syntax(strict));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_expect_bigint(handle: *mut c_void, expected: *const c_char) {
+    let expected = match BigInt::parse_lenient(CStr::from_ptr(expected).to_str().unwrap()) {
+        Ok(expected) => expected,
+        Err(err) => judge_fail!("invalid expected integer: {}", err)
+    };
+    to_reader(handle).expect_eq::<BigInt, BigInt>(&expected);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_expect_fp_rel(
+    handle: *mut c_void, expected: f64, tolerance: f64) {
+    to_reader(handle).expect_float_eq_rel(expected, tolerance);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_expect_fp_abs_or_rel(
+    handle: *mut c_void, expected: f64, abs_tolerance: f64, rel_tolerance: f64) {
+    to_reader(handle).expect_float_eq_abs_or_rel(expected, abs_tolerance, rel_tolerance);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_expect_fp_ulp(
+    handle: *mut c_void, expected: f64, max_ulps: u64) {
+    to_reader(handle).expect_float_eq_ulp(expected, max_ulps);
+}
+
+/// Get the variable name passed through FFI, which may be `NULL`.
+unsafe fn to_name<'a>(name: *const c_char) -> &'a str {
+    if name.is_null() {
+        "value"
+    } else {
+        CStr::from_ptr(name).to_str().unwrap()
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_expect_i64_range(
+    handle: *mut c_void, lo: i64, hi: i64, name: *const c_char) -> i64 {
+    to_reader(handle).expect_int_in_range(lo, hi, to_name(name))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_expect_fp_range(
+    handle: *mut c_void, lo: f64, hi: f64, name: *const c_char) -> f64 {
+    to_reader(handle).expect_float_in_range(lo, hi, to_name(name))
+}
+
+/// Copy the given values into the caller-provided buffer, which must be able to
+/// hold all of them. The buffer is not touched if there are no values, so it may
+/// be NULL in that case.
+unsafe fn fill_buffer<T: Copy>(buffer: *mut T, values: &[T]) {
+    if values.is_empty() {
+        return;
+    }
+    std::slice::from_raw_parts_mut(buffer, values.len()).copy_from_slice(values);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_expect_i32_vec(
+    handle: *mut c_void, buffer: *mut i32, n: usize, name: *const c_char) {
+    fill_buffer(buffer, &to_reader(handle).expect_vec::<i32>(n, to_name(name)));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_expect_i64_vec(
+    handle: *mut c_void, buffer: *mut i64, n: usize, name: *const c_char) {
+    fill_buffer(buffer, &to_reader(handle).expect_vec::<i64>(n, to_name(name)));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_expect_u64_vec(
+    handle: *mut c_void, buffer: *mut u64, n: usize, name: *const c_char) {
+    fill_buffer(buffer, &to_reader(handle).expect_vec::<u64>(n, to_name(name)));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_expect_fp_vec(
+    handle: *mut c_void, buffer: *mut f64, n: usize, name: *const c_char) {
+    fill_buffer(buffer, &to_reader(handle).expect_vec::<f64>(n, to_name(name)));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_expect_i64_vec_range(
+    handle: *mut c_void, buffer: *mut i64, n: usize, lo: i64, hi: i64, 
+    name: *const c_char) {
+    fill_buffer(buffer, &to_reader(handle).expect_vec_in_range(n, lo, hi, to_name(name)));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_expect_permutation(
+    handle: *mut c_void, buffer: *mut usize, n: usize, name: *const c_char) {
+    fill_buffer(buffer, &to_reader(handle).expect_permutation(n, to_name(name)));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_expect_i64_matrix(
+    handle: *mut c_void, buffer: *mut i64, rows: usize, cols: usize, 
+    name: *const c_char) {
+    let matrix = to_reader(handle).expect_matrix::<i64>(rows, cols, to_name(name));
+    for (i, row) in matrix.iter().enumerate() {
+        fill_buffer(buffer.add(i * cols), row);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_expect_eof(handle: *mut c_void) {
     to_reader(handle).expect_eof();
 }
 
@@ -239,10 +518,10 @@ pub unsafe extern fn wave_expect_eof(handle: *mut c_void) {
 const EQUAL: i32 = 0;
 const LESS: i32 = -1;
 const GREATER: i32 = 1;
-const NOT_COMPARABLE: i32 = std::i32::MAX;
+const NOT_COMPARABLE: i32 = i32::MAX;
 
 #[no_mangle]
-pub unsafe extern fn wave_cmp_fp(actual: f64, expected: f64, tolerance: f64) 
+pub unsafe extern "C" fn wave_cmp_fp(actual: f64, expected: f64, tolerance: f64) 
     -> i32 {
     match compare_floats(expected, actual, tolerance) {
         Some(Ordering::Less) => LESS,
@@ -253,12 +532,74 @@ pub unsafe extern fn wave_cmp_fp(actual: f64, expected: f64, tolerance: f64)
 }
 
 #[no_mangle]
-pub unsafe extern fn wave_cmp_str(actual: *const c_char, 
+pub unsafe extern "C" fn wave_cmp_fp_rel(actual: f64, expected: f64, tolerance: f64) 
+    -> i32 {
+    // `expected` is the reference value of the relative error, while the result
+    // orders `expected` against `actual` as `wave_cmp_fp` does.
+    match compare_floats_rel(actual, expected, tolerance).map(Ordering::reverse) {
+        Some(Ordering::Less) => LESS,
+        Some(Ordering::Equal) => EQUAL,
+        Some(Ordering::Greater) => GREATER,
+        None => NOT_COMPARABLE
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_cmp_fp_abs_or_rel(actual: f64, expected: f64, 
+    abs_tolerance: f64, rel_tolerance: f64) -> i32 {
+    match compare_floats_abs_or_rel(actual, expected, abs_tolerance, rel_tolerance)
+        .map(Ordering::reverse) {
+        Some(Ordering::Less) => LESS,
+        Some(Ordering::Equal) => EQUAL,
+        Some(Ordering::Greater) => GREATER,
+        None => NOT_COMPARABLE
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_cmp_fp_ulp(actual: f64, expected: f64, max_ulps: u64) 
+    -> i32 {
+    match compare_floats_ulp(expected, actual, max_ulps) {
+        Some(Ordering::Less) => LESS,
+        Some(Ordering::Equal) => EQUAL,
+        Some(Ordering::Greater) => GREATER,
+        None => NOT_COMPARABLE
+    }
+}
+
+/// Get the decimal syntax selected through FFI.
+fn to_decimal_syntax(strict: i32) -> DecimalSyntax {
+    if strict != 0 {
+        DecimalSyntax::strict()
+    } else {
+        DecimalSyntax::default()
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_cmp_decimal(actual: *const c_char, 
+    expected: *const c_char, precision: u32, strict: i32) -> i32 {
+    let syntax = to_decimal_syntax(strict);
+    let actual = Decimal::parse(CStr::from_ptr(actual).to_str().unwrap(), &syntax);
+    let expected = Decimal::parse(CStr::from_ptr(expected).to_str().unwrap(), &syntax);
+    match (actual, expected) {
+        (Ok(actual), Ok(expected)) => match compare_decimals(&expected, &actual, precision) {
+            Some(Ordering::Less) => LESS,
+            Some(Ordering::Equal) => EQUAL,
+            Some(Ordering::Greater) => GREATER,
+            None => NOT_COMPARABLE
+        },
+        _ => NOT_COMPARABLE
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wave_cmp_str(actual: *const c_char, 
     expected: *const c_char) -> i32 {
     let actual = CStr::from_ptr(actual).to_str().unwrap();
     let expected = CStr::from_ptr(expected).to_str().unwrap();
 
-    match actual.cmp(&expected) {
+    match actual.cmp(expected) {
         Ordering::Less => LESS,
         Ordering::Equal => EQUAL,
         Ordering::Greater => GREATER
@@ -269,7 +610,7 @@ const TRUE: i32 = 1;
 const FALSE: i32 = 0;
 
 #[no_mangle]
-pub unsafe extern fn wave_cmp_str_eq(
+pub unsafe extern "C" fn wave_cmp_str_eq(
     actual: *const c_char, expected: *const c_char, ignore_case: bool) -> i32 {
     let actual = CStr::from_ptr(actual).to_str().unwrap();
     let expected = CStr::from_ptr(expected).to_str().unwrap();
@@ -287,3 +628,47 @@ pub unsafe extern fn wave_cmp_str_eq(
         FALSE
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, File};
+
+    use crate::JudgeReader;
+    use crate::contract::ReadErrorKind;
+    use crate::tokenized::TokenizedReader;
+
+    use super::*;
+
+    fn create_test_reader(name: &str, content: &str) -> JudgeReader {
+        let path = std::env::temp_dir().join(
+            format!("wave_test_lib_{}_{}", std::process::id(), name));
+        fs::write(&path, content).unwrap();
+        let reader = JudgeReader::new(TokenizedReader::new(File::open(&path).unwrap()));
+        fs::remove_file(&path).unwrap();
+        reader
+    }
+
+    #[test]
+    fn test_number_format() {
+        let mut reader = create_test_reader("ffi_number_format", "+1 +2 007");
+        let handle = unsafe { to_raw(&mut reader) };
+
+        let err = unsafe { to_reader(handle) }.try_expect_i64().unwrap_err();
+        assert_eq!(err.kind, ReadErrorKind::InvalidToken);
+
+        unsafe { wave_set_number_format(handle, TRUE, TRUE) };
+        assert_eq!(unsafe { wave_expect_i32(handle) }, 2);
+        assert_eq!(unsafe { wave_expect_u8(handle) }, 7);
+    }
+
+    #[test]
+    fn test_fill_buffer() {
+        let mut buffer = [0i64; 3];
+        unsafe {
+            fill_buffer(buffer.as_mut_ptr(), &[1, 2, 3]);
+            fill_buffer(std::ptr::null_mut::<i64>(), &[]);
+        }
+        assert_eq!(buffer, [1, 2, 3]);
+    }
+}
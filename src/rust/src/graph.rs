@@ -0,0 +1,355 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::contract::{quit, JudgeReader, ReadError};
+use crate::tokenized::TokenizedRead;
+
+
+/// An undirected graph whose vertices are numbered from 1 to `n`, which is the
+/// usual convention of problem statements. Self-loops and multiple edges are
+/// allowed in the graph itself and can be ruled out with the checks below.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Graph {
+    n: usize,
+    edges: Vec<(usize, usize)>
+}
+
+/// Disjoint set union over the vertices of a [`Graph`].
+struct DisjointSet {
+    parent: Vec<usize>
+}
+
+impl DisjointSet {
+    fn new(n: usize) -> DisjointSet {
+        DisjointSet { parent: (0..=n).collect() }
+    }
+
+    fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        let mut x = x;
+        while self.parent[x] != root {
+            let next = self.parent[x];
+            self.parent[x] = root;
+            x = next;
+        }
+        root
+    }
+
+    /// Merge the sets containing `x` and `y`. Returns `false` if they are
+    /// already in the same set.
+    fn union(&mut self, x: usize, y: usize) -> bool {
+        let (x, y) = (self.find(x), self.find(y));
+        if x == y {
+            return false;
+        }
+        self.parent[x] = y;
+        true
+    }
+}
+
+/// Normalize the given undirected edge so that the smaller endpoint comes first.
+fn edge_key(u: usize, v: usize) -> (usize, usize) {
+    if u <= v { (u, v) } else { (v, u) }
+}
+
+impl Graph {
+    /// Create a new [`Graph`] instance with `n` vertices and no edges.
+    pub fn new(n: usize) -> Graph {
+        Graph { n, edges: Vec::new() }
+    }
+
+    /// Create a new [`Graph`] instance with `n` vertices and the given edges.
+    /// Panics if any endpoint is not in the range of `[1, n]`.
+    pub fn from_edges(n: usize, edges: &[(usize, usize)]) -> Graph {
+        let mut graph = Graph::new(n);
+        for &(u, v) in edges {
+            graph.add_edge(u, v);
+        }
+        graph
+    }
+
+    /// Add an undirected edge between `u` and `v`. Panics if any endpoint is not
+    /// in the range of `[1, n]`.
+    pub fn add_edge(&mut self, u: usize, v: usize) {
+        assert!(1 <= u && u <= self.n && 1 <= v && v <= self.n,
+            "edge ({}, {}) is out of the range of vertices", u, v);
+        self.edges.push((u, v));
+    }
+
+    /// Get the number of vertices.
+    pub fn vertex_count(&self) -> usize {
+        self.n
+    }
+
+    /// Get the edges, in the order they were added.
+    pub fn edges(&self) -> &[(usize, usize)] {
+        &self.edges
+    }
+
+    /// Check that the graph is connected. A graph without vertices is connected.
+    pub fn is_connected(&self) -> Result<(), String> {
+        let mut set = DisjointSet::new(self.n);
+        for &(u, v) in &self.edges {
+            set.union(u, v);
+        }
+        for v in 2..=self.n {
+            if set.find(v) != set.find(1) {
+                return Err(format!("vertex {} is not reachable from vertex 1", v));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the graph contains no self-loops.
+    pub fn no_self_loops(&self) -> Result<(), String> {
+        match self.edges.iter().position(|&(u, v)| u == v) {
+            Some(i) => Err(format!("edge {} ({}, {}) is a self-loop",
+                i + 1, self.edges[i].0, self.edges[i].1)),
+            None => Ok(())
+        }
+    }
+
+    /// Check that no two edges connect the same pair of vertices.
+    pub fn no_multi_edges(&self) -> Result<(), String> {
+        let mut first_seen = HashMap::with_capacity(self.edges.len());
+        for (i, &(u, v)) in self.edges.iter().enumerate() {
+            if let Some(j) = first_seen.insert(edge_key(u, v), i + 1) {
+                return Err(format!("edge {} ({}, {}) duplicates edge {}", i + 1, u, v, j));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the graph is a tree, i.e. connected and acyclic.
+    pub fn is_tree(&self) -> Result<(), String> {
+        if self.n == 0 {
+            return Err(String::from("a tree must have at least one vertex"));
+        }
+        if self.edges.len() != self.n - 1 {
+            return Err(format!("a tree with {} vertices has {} edges, found {}",
+                self.n, self.n - 1, self.edges.len()));
+        }
+
+        let mut set = DisjointSet::new(self.n);
+        for (i, &(u, v)) in self.edges.iter().enumerate() {
+            if !set.union(u, v) {
+                return Err(format!("edge {} ({}, {}) forms a cycle", i + 1, u, v));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that every edge of this graph is also an edge of `other`, and the
+    /// two graphs have the same vertices.
+    pub fn is_subgraph_of(&self, other: &Graph) -> Result<(), String> {
+        if self.n != other.n {
+            return Err(format!("expected {} vertices, found {}", other.n, self.n));
+        }
+
+        let mut available = HashMap::with_capacity(other.edges.len());
+        for &(u, v) in &other.edges {
+            *available.entry(edge_key(u, v)).or_insert(0usize) += 1;
+        }
+        for (i, &(u, v)) in self.edges.iter().enumerate() {
+            match available.get_mut(&edge_key(u, v)) {
+                Some(count) if *count > 0 => *count -= 1,
+                _ => return Err(format!("edge {} ({}, {}) does not exist in the original graph",
+                    i + 1, u, v))
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that this graph is a spanning tree of `other`.
+    pub fn is_spanning_tree_of(&self, other: &Graph) -> Result<(), String> {
+        self.is_subgraph_of(other)?;
+        self.is_tree()
+    }
+
+    /// Check that no vertex is covered by more than one edge.
+    pub fn is_matching(&self) -> Result<(), String> {
+        let mut covered_by = vec![0; self.n + 1];
+        for (i, &(u, v)) in self.edges.iter().enumerate() {
+            if u == v {
+                return Err(format!("edge {} ({}, {}) is a self-loop", i + 1, u, v));
+            }
+            for &x in &[u, v] {
+                if covered_by[x] != 0 {
+                    return Err(format!("vertex {} is covered by edge {} and edge {}",
+                        x, covered_by[x], i + 1));
+                }
+                covered_by[x] = i + 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the given sequence of vertices is a path in the graph, i.e.
+    /// every two consecutive vertices are connected by an edge. Vertices may be
+    /// visited more than once.
+    pub fn path_is_valid(&self, path: &[usize]) -> Result<(), String> {
+        if let Some(i) = path.iter().position(|&v| v < 1 || v > self.n) {
+            return Err(format!("vertex {} of the path ({}) does not exist", i + 1, path[i]));
+        }
+
+        let adjacent: HashSet<(usize, usize)> = self.edges.iter()
+            .map(|&(u, v)| edge_key(u, v))
+            .collect();
+        for (i, step) in path.windows(2).enumerate() {
+            if !adjacent.contains(&edge_key(step[0], step[1])) {
+                return Err(format!("no edge between vertex {} and vertex {} (step {} of the path)",
+                    step[0], step[1], i + 1));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the given sequence of vertices is a simple path in the graph,
+    /// i.e. a valid path that visits every vertex at most once.
+    pub fn path_is_simple(&self, path: &[usize]) -> Result<(), String> {
+        self.path_is_valid(path)?;
+
+        let mut first_seen = vec![0; self.n + 1];
+        for (i, &v) in path.iter().enumerate() {
+            if first_seen[v] != 0 {
+                return Err(format!("vertex {} is visited twice, at position {} and {} of the path",
+                    v, first_seen[v], i + 1));
+            }
+            first_seen[v] = i + 1;
+        }
+        Ok(())
+    }
+}
+
+impl<T: TokenizedRead> JudgeReader<T> {
+    /// Exit the program with the failure verdict of this reader reporting the
+    /// reason if the given result of a graph check on a graph read from this
+    /// reader is an error, e.g. `reader.expect_satisfied(graph.is_tree())`.
+    pub fn expect_satisfied(&self, result: Result<(), String>) {
+        if let Err(reason) = result {
+            quit(self.failure_verdict(), Some(&reason));
+        }
+    }
+
+    /// Expect the next `m` pairs of tokens from the inner reader are edges of a
+    /// graph with `n` vertices, i.e. both endpoints are in the range of `[1, n]`.
+    /// Errors name the offending edge by its 1-based index, e.g. `edge 3 of g`.
+    pub fn try_expect_graph(&mut self, n: usize, m: usize, name: &str)
+        -> Result<Graph, ReadError> {
+        let mut graph = Graph::new(n);
+        for i in 1..=m {
+            let edge_name = format!("edge {} of {}", i, name);
+            let u = self.try_expect_int_in_range(1, n, "u")
+                .map_err(|err| err.with_name(&edge_name))?;
+            let v = self.try_expect_int_in_range(1, n, "v")
+                .map_err(|err| err.with_name(&edge_name))?;
+            graph.add_edge(u, v);
+        }
+        Ok(graph)
+    }
+
+    /// Expect the next `m` pairs of tokens from the inner reader are edges of a
+    /// graph with `n` vertices.
+    pub fn expect_graph(&mut self, n: usize, m: usize, name: &str) -> Graph {
+        let result = self.try_expect_graph(n, m, name);
+        self.unwrap_or_fail(result)
+    }
+
+    /// Expect the next `len` tokens from the inner reader are vertices of a path,
+    /// each in the range of `[1, n]`.
+    pub fn try_expect_path(&mut self, n: usize, len: usize, name: &str)
+        -> Result<Vec<usize>, ReadError> {
+        self.try_expect_vec_in_range(len, 1, n, name)
+    }
+
+    /// Expect the next `len` tokens from the inner reader are vertices of a path,
+    /// each in the range of `[1, n]`.
+    pub fn expect_path(&mut self, n: usize, len: usize, name: &str) -> Vec<usize> {
+        let result = self.try_expect_path(n, len, name);
+        self.unwrap_or_fail(result)
+    }
+}
+
+pub mod preclude {
+    pub use super::Graph;
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::contract::{JudgeReader, ReadErrorKind};
+    use crate::tokenized::TokenizedReader;
+
+    use super::Graph;
+
+    #[test]
+    fn test_tree() {
+        let tree = Graph::from_edges(4, &[(1, 2), (2, 3), (2, 4)]);
+        assert_eq!(tree.is_tree(), Ok(()));
+        assert_eq!(tree.is_connected(), Ok(()));
+
+        let cycle = Graph::from_edges(4, &[(1, 2), (2, 3), (3, 1)]);
+        assert_eq!(cycle.is_tree(), Err(String::from("edge 3 (3, 1) forms a cycle")));
+        assert_eq!(cycle.is_connected(),
+            Err(String::from("vertex 4 is not reachable from vertex 1")));
+
+        let forest = Graph::from_edges(4, &[(1, 2)]);
+        assert_eq!(forest.is_tree(),
+            Err(String::from("a tree with 4 vertices has 3 edges, found 1")));
+    }
+
+    #[test]
+    fn test_edges() {
+        let graph = Graph::from_edges(3, &[(1, 2), (2, 3), (2, 1)]);
+        assert_eq!(graph.no_self_loops(), Ok(()));
+        assert_eq!(graph.no_multi_edges(),
+            Err(String::from("edge 3 (2, 1) duplicates edge 1")));
+        assert_eq!(Graph::from_edges(2, &[(2, 2)]).no_self_loops(),
+            Err(String::from("edge 1 (2, 2) is a self-loop")));
+    }
+
+    #[test]
+    fn test_subgraph() {
+        let graph = Graph::from_edges(4, &[(1, 2), (2, 3), (3, 4), (4, 1)]);
+        assert_eq!(Graph::from_edges(4, &[(2, 1), (3, 2), (4, 3)]).is_spanning_tree_of(&graph),
+            Ok(()));
+        assert_eq!(Graph::from_edges(4, &[(1, 3)]).is_subgraph_of(&graph),
+            Err(String::from("edge 1 (1, 3) does not exist in the original graph")));
+        assert_eq!(Graph::from_edges(4, &[(1, 2), (1, 2)]).is_subgraph_of(&graph),
+            Err(String::from("edge 2 (1, 2) does not exist in the original graph")));
+    }
+
+    #[test]
+    fn test_matching() {
+        assert_eq!(Graph::from_edges(4, &[(1, 2), (3, 4)]).is_matching(), Ok(()));
+        assert_eq!(Graph::from_edges(4, &[(1, 2), (2, 3)]).is_matching(),
+            Err(String::from("vertex 2 is covered by edge 1 and edge 2")));
+    }
+
+    #[test]
+    fn test_path() {
+        let graph = Graph::from_edges(4, &[(1, 2), (2, 3), (3, 4)]);
+        assert_eq!(graph.path_is_simple(&[1, 2, 3, 4]), Ok(()));
+        assert_eq!(graph.path_is_valid(&[1, 2, 1]), Ok(()));
+        assert_eq!(graph.path_is_simple(&[1, 2, 1]),
+            Err(String::from("vertex 1 is visited twice, at position 1 and 3 of the path")));
+        assert_eq!(graph.path_is_valid(&[1, 3]),
+            Err(String::from("no edge between vertex 1 and vertex 3 (step 1 of the path)")));
+        assert_eq!(graph.path_is_valid(&[1, 5]),
+            Err(String::from("vertex 2 of the path (5) does not exist")));
+    }
+
+    #[test]
+    fn test_expect_graph() {
+        let mut reader = JudgeReader::new(TokenizedReader::new("1 2\n2 3\n3 5\n".as_bytes()));
+        let graph = reader.try_expect_graph(4, 2, "g").unwrap();
+        assert_eq!(graph.edges(), &[(1, 2), (2, 3)]);
+
+        let err = reader.try_expect_graph(4, 1, "h").unwrap_err();
+        assert_eq!(err.kind, ReadErrorKind::OutOfRange);
+        assert_eq!(err.name.as_deref(), Some("edge 1 of h"));
+    }
+}
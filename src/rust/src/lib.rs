@@ -1,28 +1,479 @@
 pub mod tokenized;
+pub mod strict;
 pub mod cmp;
+pub mod bigint;
 #[macro_use]
 pub mod contract;
+pub mod graph;
+pub mod report;
 pub mod ffi;
 
-use std::fs::File;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::ffi::OsString;
+use std::fmt::{Display, Formatter};
+use std::fs::{File, OpenOptions};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::contract::{ReadError, ReadErrorKind, Verdict};
+use crate::report::{Protocol, ResultFile, ResultFormat};
+use crate::report::{set_protocol, set_feedback_dir, set_judge_log_file, set_result_file};
+use crate::strict::StrictReader;
+use crate::tokenized::{NumberFormat, TokenizedReader};
 
 
 /// Type of the formatted input reader used in `WaveTestLib`.
 pub type JudgeReader = crate::contract::JudgeReader<crate::tokenized::TokenizedReader<File>>;
 
+/// Type of the strict formatted input reader used by [`Validator`].
+pub type StrictJudgeReader = crate::contract::JudgeReader<crate::strict::StrictReader<File>>;
+
+/// Error raised when a [`Checker`] or an [`Interactor`] cannot be built.
+#[derive(Debug)]
+pub enum InitError {
+    /// A required command line argument is missing. The name of the missing
+    /// argument is carried.
+    MissingArgument(&'static str),
+
+    /// An unexpected extra command line argument is given.
+    UnexpectedArgument(OsString),
+
+    /// A command line option is unknown or has an invalid value. The reason is
+    /// carried.
+    InvalidOption(String),
+
+    /// A judge file cannot be opened.
+    Io {
+        path: PathBuf,
+        error: std::io::Error
+    },
+
+    /// A file descriptor given on the command line is invalid or closed.
+    InvalidDescriptor {
+        fd: i32,
+        error: std::io::Error
+    }
+}
+
+impl Display for InitError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            InitError::MissingArgument(name) =>
+                write!(f, "missing command line argument: <{}>", name),
+            InitError::UnexpectedArgument(arg) =>
+                write!(f, "unexpected command line argument: \"{}\"", 
+                    arg.to_string_lossy()),
+            InitError::InvalidOption(reason) =>
+                write!(f, "invalid command line option: {}", reason),
+            InitError::Io { path, error } =>
+                write!(f, "cannot open \"{}\": {}", path.display(), error),
+            InitError::InvalidDescriptor { fd, error } =>
+                write!(f, "invalid file descriptor {}: {}", fd, error)
+        }
+    }
+}
+
+impl std::error::Error for InitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InitError::Io { error, .. } => Some(error),
+            InitError::InvalidDescriptor { error, .. } => Some(error),
+            _ => None
+        }
+    }
+}
+
+/// Open the judge file at the given path and create a [`JudgeReader`] around it.
+/// Expectations failed on the reader are treated as judge failures.
+fn open_reader<P: AsRef<Path>>(path: P) -> Result<JudgeReader, InitError> {
+    let path = path.as_ref();
+    match File::open(path) {
+        Ok(file) => Ok(JudgeReader::with_failure_verdict(
+            TokenizedReader::new(file), Verdict::JudgeFailure)),
+        Err(error) => Err(InitError::Io { path: path.to_path_buf(), error })
+    }
+}
+
+/// One end of the pipe that connects the interactor and the user's program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PipeEnd {
+    /// The pipe end is a named pipe (FIFO) located at the given path.
+    Path(PathBuf),
+
+    /// The pipe end is a file descriptor inherited from the parent process. The
+    /// descriptors of the standard streams, i.e. 0, 1 and 2, are not accepted.
+    Fd(i32)
+}
+
+impl PipeEnd {
+    /// Parse a [`PipeEnd`] from a command line argument. An argument consisting
+    /// of decimal digits only is treated as a file descriptor; any other argument
+    /// is treated as a path. Use a path like `./3` to refer to a file named `3`.
+    pub fn from_arg<S: Into<OsString>>(arg: S) -> PipeEnd {
+        let arg = arg.into();
+        let fd = arg.to_str()
+            .filter(|s| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|s| s.parse::<i32>().ok());
+        match fd {
+            Some(fd) => PipeEnd::Fd(fd),
+            None => PipeEnd::Path(PathBuf::from(arg))
+        }
+    }
+
+    /// Open the pipe end for reading.
+    fn open_read(&self) -> Result<File, InitError> {
+        match self {
+            PipeEnd::Path(path) => File::open(path)
+                .map_err(|error| InitError::Io { path: path.clone(), error }),
+            PipeEnd::Fd(fd) => open_fd(*fd)
+        }
+    }
+
+    /// Open the pipe end for writing.
+    fn open_write(&self) -> Result<File, InitError> {
+        match self {
+            PipeEnd::Path(path) => OpenOptions::new().write(true).open(path)
+                .map_err(|error| InitError::Io { path: path.clone(), error }),
+            PipeEnd::Fd(fd) => open_fd(*fd)
+        }
+    }
+}
+
+/// Take the ownership of the given inherited file descriptor. Fails if the file
+/// descriptor is negative, one of the standard streams, or not open. The
+/// descriptor is validated before its ownership is taken, so it is never closed
+/// on failure.
+#[cfg(unix)]
+fn open_fd(fd: i32) -> Result<File, InitError> {
+    use std::os::raw::c_int;
+    use std::os::unix::io::FromRawFd;
+
+    extern "C" {
+        fn fcntl(fd: c_int, cmd: c_int, ...) -> c_int;
+    }
+    const F_GETFD: c_int = 1;
+
+    if fd < 0 {
+        return Err(InitError::InvalidDescriptor {
+            fd,
+            error: std::io::Error::new(ErrorKind::InvalidInput, "negative file descriptor")
+        });
+    }
+    if fd <= 2 {
+        return Err(InitError::InvalidDescriptor {
+            fd,
+            error: std::io::Error::new(ErrorKind::InvalidInput, 
+                "file descriptor of a standard stream")
+        });
+    }
+
+    if unsafe { fcntl(fd, F_GETFD) } == -1 {
+        return Err(InitError::InvalidDescriptor { fd, error: std::io::Error::last_os_error() });
+    }
+    Ok(unsafe { File::from_raw_fd(fd) })
+}
+
+#[cfg(not(unix))]
+fn open_fd(fd: i32) -> Result<File, InitError> {
+    Err(InitError::InvalidDescriptor {
+        fd,
+        error: std::io::Error::new(ErrorKind::Other, 
+            "file descriptors are not supported on this platform")
+    })
+}
+
+/// Options that can be given on the command line of checkers and interactors,
+/// in the form of either `--name value` or `--name=value`.
+#[derive(Debug, Default)]
+struct Options {
+    /// Value of `--result-file`.
+    result_file: Option<PathBuf>,
+
+    /// Value of `--result-format`.
+    result_format: Option<ResultFormat>,
+
+    /// Value of `--judge-log`.
+    judge_log: Option<PathBuf>
+}
+
+impl Options {
+    /// Extract the options from the given command line arguments. The first item
+    /// yielded by `args` is the name of the program and is ignored. Returns the
+    /// remaining positional arguments along with the options. An argument `--`
+    /// stops option parsing.
+    fn extract<I, S>(args: I) -> Result<(std::vec::IntoIter<OsString>, Options), InitError>
+        where I: IntoIterator<Item = S>,
+              S: Into<OsString> {
+        let mut options = Options::default();
+        let mut positional = Vec::new();
+
+        let mut args = args.into_iter().map(Into::into).skip(1);
+        while let Some(arg) = args.next() {
+            let (name, inline_value) = match arg.to_str() {
+                Some("--") => {
+                    positional.extend(args);
+                    break;
+                },
+                Some(s) if s.starts_with("--") => match s.find('=') {
+                    Some(i) => (String::from(&s[..i]), Some(OsString::from(&s[i + 1..]))),
+                    None => (String::from(s), None)
+                },
+                _ => {
+                    positional.push(arg);
+                    continue;
+                }
+            };
+            let value = match inline_value.or_else(|| args.next()) {
+                Some(value) => value,
+                None => return Err(InitError::InvalidOption(
+                    format!("missing value of option {}", name)))
+            };
+
+            match name.as_str() {
+                "--result-file" => options.result_file = Some(PathBuf::from(value)),
+                "--judge-log" => options.judge_log = Some(PathBuf::from(value)),
+                "--result-format" => {
+                    let format = value.to_string_lossy().parse()
+                        .map_err(InitError::InvalidOption)?;
+                    options.result_format = Some(format);
+                },
+                _ => return Err(InitError::InvalidOption(format!("unknown option {}", name)))
+            }
+        }
+
+        Ok((positional.into_iter(), options))
+    }
+
+    /// Apply the options to the global judge configurations.
+    fn apply(self) -> Result<(), InitError> {
+        match (self.result_file, self.result_format) {
+            (Some(path), format) => set_result_file(Some(ResultFile {
+                path,
+                format: format.unwrap_or(ResultFormat::Json)
+            })),
+            (None, Some(..)) => return Err(InitError::InvalidOption(
+                String::from("--result-format is given without --result-file"))),
+            (None, None) => ()
+        };
+        if let Some(judge_log) = self.judge_log {
+            set_judge_log_file(Some(judge_log));
+        }
+        Ok(())
+    }
+}
+
+/// Apply the judge configurations given by environment variables. Exits the
+/// program with a judge failure result if any of them is invalid.
+fn configure_from_env() {
+    match ResultFile::from_env() {
+        Ok(Some(result_file)) => set_result_file(Some(result_file)),
+        Ok(None) => (),
+        Err(err) => judge_fail!("invalid environment variable: {}", err)
+    }
+    match std::env::var_os(crate::report::ENV_JUDGE_LOG) {
+        Some(path) if !path.is_empty() => set_judge_log_file(Some(PathBuf::from(path))),
+        _ => ()
+    }
+}
+
+/// Take the next positional argument with the given name from the iterator.
+fn next_arg<I>(args: &mut I, name: &'static str) -> Result<OsString, InitError>
+    where I: Iterator<Item = OsString> {
+    args.next().ok_or(InitError::MissingArgument(name))
+}
+
+/// Ensure that there are no more arguments left in the iterator.
+fn expect_no_more_args<I>(args: &mut I) -> Result<(), InitError>
+    where I: Iterator<Item = OsString> {
+    match args.next() {
+        Some(arg) => Err(InitError::UnexpectedArgument(arg)),
+        None => Ok(())
+    }
+}
+
+/// Duplicate the standard input of the process into a [`File`].
+fn stdin_file() -> Result<File, InitError> {
+    #[cfg(unix)]
+    let owned = {
+        use std::os::unix::io::AsFd;
+        std::io::stdin().as_fd().try_clone_to_owned()
+    };
+    #[cfg(windows)]
+    let owned = {
+        use std::os::windows::io::AsHandle;
+        std::io::stdin().as_handle().try_clone_to_owned()
+    };
+
+    owned.map(File::from)
+        .map_err(|error| InitError::Io { path: PathBuf::from("<stdin>"), error })
+}
+
+/// Get the protocol specified by the environment variables, or the native
+/// [`Protocol::Wave`] protocol if not specified. Exits the program with a judge
+/// failure result if the environment variable is invalid.
+fn protocol_from_env() -> Protocol {
+    match Protocol::from_env() {
+        Ok(protocol) => protocol.unwrap_or(Protocol::Wave),
+        Err(err) => judge_fail!("invalid environment variable: {}", err)
+    }
+}
+
 /// A [`Checker`] instance is the core object in a checker implemented using `WaveTestLib.`
 pub struct Checker {
     input: JudgeReader,
     std_answer: JudgeReader,
-    user_answer: JudgeReader
+    user_answer: JudgeReader,
+    extra_args: Vec<OsString>
 }
 
 impl Checker {
     /// Create a new [`Checker`] instance. Information required to build the instance
-    /// is collected in the command line arguments of the program.
+    /// is collected in the command line arguments of the program and in the
+    /// environment variables. The protocol is selected by the `WAVE_PROTOCOL`
+    /// environment variable and defaults to [`Protocol::Wave`]; see
+    /// [`Checker::from_protocol_args`] for the layout of the arguments. Exits the
+    /// program with a judge failure result if the instance cannot be built.
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Checker {
-        // TODO: Implement Checker::new
-        unimplemented!()
+        Checker::with_protocol(protocol_from_env())
+    }
+
+    /// Create a new [`Checker`] instance that speaks the given protocol with the
+    /// judge system. Information required to build the instance is collected in
+    /// the command line arguments of the program and in the environment variables.
+    /// Exits the program with a judge failure result if the instance cannot be built.
+    pub fn with_protocol(protocol: Protocol) -> Checker {
+        set_protocol(protocol);
+        configure_from_env();
+        match Checker::from_protocol_args(protocol, std::env::args_os()) {
+            Ok(checker) => checker,
+            Err(err) => judge_fail!("{}", err)
+        }
+    }
+
+    /// Create a new [`Checker`] instance from the given command line arguments
+    /// under the [`Protocol::Wave`] protocol. See [`Checker::from_protocol_args`].
+    pub fn from_args<I, S>(args: I) -> Result<Checker, InitError>
+        where I: IntoIterator<Item = S>,
+              S: Into<OsString> {
+        Checker::from_protocol_args(Protocol::Wave, args)
+    }
+
+    /// Create a new [`Checker`] instance from the given command line arguments and
+    /// make the given protocol the current protocol. The first item yielded by
+    /// `args` is the name of the program and is ignored, the same as
+    /// [`std::env::args_os`]. The layout of the remaining arguments depends on the
+    /// protocol:
+    ///
+    /// * [`Protocol::Wave`]: `[options] <input> <std_answer> <user_answer>`. The
+    ///   following options are recognized:
+    ///   * `--result-file <path>`: write a structured result file to the given path;
+    ///   * `--result-format <json|xml>`: format of the result file, defaults to JSON;
+    ///   * `--judge-log <path>`: write the judge log to the given path, see
+    ///     [`crate::report::judge_log_path`] for the default.
+    /// * [`Protocol::Kattis`]: `<input> <std_answer> <feedback_dir> [extra_args...]`.
+    ///   The user's output is read from stdin. Extra arguments are available
+    ///   through [`Checker::extra_args`].
+    /// * [`Protocol::Testlib`]: `<input> <user_answer> <std_answer> [<result_file>
+    ///   [-appes]]`. Note that the user's output precedes the standard answer. The
+    ///   result file is written in the testlib XML format if `-appes` is given, or
+    ///   in the testlib plain text format otherwise.
+    /// * [`Protocol::Cms`]: `<input> <std_answer> <user_answer>`.
+    pub fn from_protocol_args<I, S>(protocol: Protocol, args: I) -> Result<Checker, InitError>
+        where I: IntoIterator<Item = S>,
+              S: Into<OsString> {
+        Checker::from_protocol_args_with_stdin(protocol, args, stdin_file)
+    }
+
+    /// Create a new [`Checker`] instance the same as [`Checker::from_protocol_args`],
+    /// calling `stdin` to open the user's output under the [`Protocol::Kattis`]
+    /// protocol.
+    fn from_protocol_args_with_stdin<I, S, F>(protocol: Protocol, args: I, stdin: F)
+        -> Result<Checker, InitError>
+        where I: IntoIterator<Item = S>,
+              S: Into<OsString>,
+              F: FnOnce() -> Result<File, InitError> {
+        set_protocol(protocol);
+        match protocol {
+            Protocol::Wave => {
+                let (mut args, options) = Options::extract(args)?;
+                options.apply()?;
+                let input = next_arg(&mut args, "input")?;
+                let std_answer = next_arg(&mut args, "std_answer")?;
+                let user_answer = next_arg(&mut args, "user_answer")?;
+                expect_no_more_args(&mut args)?;
+
+                Checker::from_paths(input, std_answer, user_answer)
+            },
+            Protocol::Testlib => {
+                let mut args = args.into_iter().map(Into::into).skip(1);
+                let input = next_arg(&mut args, "input")?;
+                let user_answer = next_arg(&mut args, "output")?;
+                let std_answer = next_arg(&mut args, "answer")?;
+                if let Some(result_file) = args.next() {
+                    let format = match args.next() {
+                        Some(ref flag) if flag == "-appes" => ResultFormat::Xml,
+                        Some(flag) => return Err(InitError::UnexpectedArgument(flag)),
+                        None => ResultFormat::Text
+                    };
+                    expect_no_more_args(&mut args)?;
+                    set_result_file(Some(ResultFile { path: PathBuf::from(result_file), format }));
+                }
+
+                Checker::from_paths(input, std_answer, user_answer)
+            },
+            Protocol::Cms => {
+                let mut args = args.into_iter().map(Into::into).skip(1);
+                let input = next_arg(&mut args, "input")?;
+                let std_answer = next_arg(&mut args, "std_answer")?;
+                let user_answer = next_arg(&mut args, "user_answer")?;
+                expect_no_more_args(&mut args)?;
+
+                Checker::from_paths(input, std_answer, user_answer)
+            },
+            Protocol::Kattis => {
+                let mut args = args.into_iter().map(Into::into).skip(1);
+                let input = next_arg(&mut args, "input")?;
+                let std_answer = next_arg(&mut args, "std_answer")?;
+                let feedback_dir = PathBuf::from(next_arg(&mut args, "feedback_dir")?);
+                if !feedback_dir.is_dir() {
+                    return Err(InitError::Io {
+                        path: feedback_dir,
+                        error: std::io::Error::new(ErrorKind::NotFound, "not a directory")
+                    });
+                }
+                set_feedback_dir(Some(feedback_dir));
+
+                Ok(Checker {
+                    input: open_reader(input)?,
+                    std_answer: open_reader(std_answer)?,
+                    user_answer: JudgeReader::new(TokenizedReader::new(stdin()?)),
+                    extra_args: args.collect()
+                })
+            }
+        }
+    }
+
+    /// Create a new [`Checker`] instance from the paths to the input file, the
+    /// standard answer file and the user's output file.
+    pub fn from_paths<P1, P2, P3>(input: P1, std_answer: P2, user_answer: P3)
+        -> Result<Checker, InitError>
+        where P1: AsRef<Path>,
+              P2: AsRef<Path>,
+              P3: AsRef<Path> {
+        let input = open_reader(input)?;
+        let std_answer = open_reader(std_answer)?;
+        let mut user_answer = open_reader(user_answer)?;
+        user_answer.set_failure_verdict(Verdict::WrongAnswer);
+
+        Ok(Checker { input, std_answer, user_answer, extra_args: Vec::new() })
+    }
+
+    /// Get the extra command line arguments passed to the checker by the judge
+    /// system, e.g. the validator flags of the [`Protocol::Kattis`] protocol.
+    pub fn extra_args(&self) -> &[OsString] {
+        &self.extra_args
     }
 
     /// Get the [`JudgeReader`] instance around the input file.
@@ -52,10 +503,64 @@ pub struct Interactor {
 
 impl Interactor {
     /// Create a new [`Interactor`] instance. Information required to build the instance
-    /// is collected in the command line arguments of the program.
+    /// is collected in the command line arguments of the program, which should be
+    /// in the form of `[options] <input> <answer> <read_end> <write_end>`, and in
+    /// the environment variables. See [`PipeEnd::from_arg`] for the format of the
+    /// last two arguments and [`Checker::from_args`] for available options. Exits
+    /// the program with a judge failure result if the instance cannot be built.
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Interactor {
-        // TODO: Implement Interactor::new
-        unimplemented!()
+        configure_from_env();
+        match Interactor::from_args(std::env::args_os()) {
+            Ok(interactor) => interactor,
+            Err(err) => judge_fail!("{}", err)
+        }
+    }
+
+    /// Create a new [`Interactor`] instance from the given command line arguments.
+    /// The first item yielded by `args` is the name of the program and is ignored,
+    /// the same as [`std::env::args_os`].
+    pub fn from_args<I, S>(args: I) -> Result<Interactor, InitError>
+        where I: IntoIterator<Item = S>,
+              S: Into<OsString> {
+        let (mut args, options) = Options::extract(args)?;
+        options.apply()?;
+        let input = next_arg(&mut args, "input")?;
+        let answer = next_arg(&mut args, "answer")?;
+        let read_end = PipeEnd::from_arg(next_arg(&mut args, "read_end")?);
+        let write_end = PipeEnd::from_arg(next_arg(&mut args, "write_end")?);
+        expect_no_more_args(&mut args)?;
+
+        Interactor::from_pipes(input, answer, read_end, write_end)
+    }
+
+    /// Create a new [`Interactor`] instance from the paths to the input file and the
+    /// answer file, and the two ends of the pipes connected to the user's program.
+    /// The read end is opened before the write end, so the user's program should
+    /// open named pipes in the same order to avoid deadlocks.
+    pub fn from_pipes<P1, P2>(input: P1, answer: P2, 
+        read_end: PipeEnd, write_end: PipeEnd) -> Result<Interactor, InitError>
+        where P1: AsRef<Path>,
+              P2: AsRef<Path> {
+        let input = open_reader(input)?;
+        let answer = open_reader(answer)?;
+
+        let read_file = read_end.open_read()?;
+        let write_file = match (&read_end, &write_end) {
+            // Both ends share one descriptor (e.g. a socket); duplicate it so that
+            // each end owns its own descriptor.
+            (PipeEnd::Fd(read_fd), PipeEnd::Fd(write_fd)) if read_fd == write_fd =>
+                read_file.try_clone()
+                    .map_err(|error| InitError::InvalidDescriptor { fd: *write_fd, error })?,
+            _ => write_end.open_write()?
+        };
+
+        Ok(Interactor {
+            input,
+            answer,
+            read_end: JudgeReader::new(TokenizedReader::new(read_file)),
+            write_end: write_file
+        })
     }
 
     /// Get the [`JudgeReader`] instance around the input file.
@@ -80,3 +585,462 @@ impl Interactor {
         &mut self.write_end
     }
 }
+
+
+/// Coverage of the bounds of a named variable read by a [`Validator`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BoundCoverage {
+    /// Whether a value equal to the lower bound has been read.
+    pub min_hit: bool,
+
+    /// Whether a value equal to the upper bound has been read.
+    pub max_hit: bool
+}
+
+mod sealed {
+    pub trait Sealed { }
+}
+
+/// Integer types that can be read by [`Validator::read_int_in_range`]. This
+/// trait is sealed and implemented for the primitive integer types only.
+pub trait ValidatorInt: sealed::Sealed + Copy + Ord + Display {
+    /// Parse an integer of this type from the given token, which must be in its
+    /// canonical form.
+    fn parse_canonical(token: &[u8]) -> Result<Self, &'static str>;
+}
+
+macro_rules! impl_validator_int {
+    ($parse:ident: $($t:ty),*) => {
+        $(
+            impl sealed::Sealed for $t { }
+
+            impl ValidatorInt for $t {
+                fn parse_canonical(token: &[u8]) -> Result<$t, &'static str> {
+                    let value = crate::tokenized::$parse(token, &NumberFormat::strict())?;
+                    <$t>::try_from(value).map_err(|_| "integer overflow")
+                }
+            }
+        )*
+    };
+}
+
+impl_validator_int!(parse_i64: i8, i16, i32, i64, isize);
+impl_validator_int!(parse_u64: u8, u16, u32, u64, usize);
+
+/// A [`Validator`] instance is the core object in an input validator implemented
+/// using `WaveTestLib`. The test input is read in strict mode, and any violation
+/// exits the program with a [`Verdict::InvalidInput`] verdict.
+pub struct Validator {
+    reader: StrictJudgeReader,
+    coverage: BTreeMap<String, BoundCoverage>
+}
+
+impl Validator {
+    /// Create a new [`Validator`] instance. Information required to build the
+    /// instance is collected in the command line arguments of the program, which
+    /// should be in the form of `[options] [input]`, and in the environment
+    /// variables. The test input is read from stdin if `input` is not given. See
+    /// [`Checker::from_args`] for available options. Exits the program with a
+    /// judge failure result if the instance cannot be built.
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Validator {
+        set_protocol(protocol_from_env());
+        configure_from_env();
+        match Validator::from_args(std::env::args_os()) {
+            Ok(validator) => validator,
+            Err(err) => judge_fail!("{}", err)
+        }
+    }
+
+    /// Create a new [`Validator`] instance from the given command line arguments.
+    /// The first item yielded by `args` is the name of the program and is ignored,
+    /// the same as [`std::env::args_os`].
+    pub fn from_args<I, S>(args: I) -> Result<Validator, InitError>
+        where I: IntoIterator<Item = S>,
+              S: Into<OsString> {
+        let (mut args, options) = Options::extract(args)?;
+        options.apply()?;
+        let input = args.next();
+        expect_no_more_args(&mut args)?;
+
+        match input {
+            Some(input) => Validator::from_path(input),
+            None => Ok(Validator::from_file(stdin_file()?))
+        }
+    }
+
+    /// Create a new [`Validator`] instance that validates the file at the given path.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Validator, InitError> {
+        let path = path.as_ref();
+        match File::open(path) {
+            Ok(file) => Ok(Validator::from_file(file)),
+            Err(error) => Err(InitError::Io { path: path.to_path_buf(), error })
+        }
+    }
+
+    /// Create a new [`Validator`] instance that validates the given file.
+    pub fn from_file(file: File) -> Validator {
+        Validator {
+            reader: StrictJudgeReader::with_failure_verdict(
+                StrictReader::new(file), Verdict::InvalidInput),
+            coverage: BTreeMap::new()
+        }
+    }
+
+    /// Get the [`StrictJudgeReader`] instance around the test input.
+    pub fn reader(&mut self) -> &mut StrictJudgeReader {
+        &mut self.reader
+    }
+
+    /// Get the coverage of the bounds of the named variables read so far.
+    pub fn coverage(&self) -> &BTreeMap<String, BoundCoverage> {
+        &self.coverage
+    }
+
+    /// Read an integer named `name` and expect it to be in the range of
+    /// `[lo, hi]`. The integer must be in its canonical form, i.e. without
+    /// leading zeros, `+` sign or negative zero. Whether the bounds are hit is recorded in the
+    /// coverage of `name`.
+    pub fn try_read_int_in_range<T: ValidatorInt>(&mut self, lo: T, hi: T, name: &str)
+        -> Result<T, ReadError> {
+        let token = self.reader.try_expect_type::<String>()?;
+        let value = match T::parse_canonical(token.as_bytes()) {
+            Ok(value) => value,
+            Err(reason) => return Err(ReadError::new(ReadErrorKind::InvalidToken,
+                Some(String::from(reason)), Some(token))
+                .with_name(name)
+                .with_position(self.reader.position()))
+        };
+        if value < lo || value > hi {
+            return Err(ReadError::out_of_range(&lo, &hi, name, token)
+                .with_position(self.reader.position()));
+        }
+
+        let coverage = self.coverage.entry(String::from(name)).or_default();
+        coverage.min_hit |= value == lo;
+        coverage.max_hit |= value == hi;
+        Ok(value)
+    }
+
+    /// Read an integer named `name` and expect it to be in the range of
+    /// `[lo, hi]`. See [`Validator::try_read_int_in_range`].
+    pub fn read_int_in_range<T: ValidatorInt>(&mut self, lo: T, hi: T, name: &str) -> T {
+        match self.try_read_int_in_range(lo, hi, name) {
+            Ok(value) => value,
+            Err(err) => invalid_input!("{}", err)
+        }
+    }
+
+    /// Expect EOF has been hit exactly on the test input, and exit the program
+    /// with an accepted result. The coverage of the bounds is reported as the
+    /// feedback, so that it is delivered along with the verdict under every
+    /// protocol, e.g. on stderr and in the result file.
+    pub fn finish(&mut self) -> ! {
+        self.reader.expect_eof_exact();
+        for (name, coverage) in &self.coverage {
+            feedback!("{}: min {}, max {}", name,
+                if coverage.min_hit { "hit" } else { "not hit" },
+                if coverage.max_hit { "hit" } else { "not hit" });
+        }
+        accept!("valid input")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use std::io::Write;
+    use std::sync::{Mutex, MutexGuard};
+
+    use crate::report::{ResultFile, ResultFormat};
+
+    use crate::report::Protocol;
+
+    use crate::contract::ReadErrorKind;
+
+    use super::{BoundCoverage, Checker, Interactor, InitError, Options, PipeEnd, Validator};
+
+    /// Lock held by tests that modify the global judge configurations.
+    static CONFIG_LOCK: Mutex<()> = Mutex::new(());
+
+    /// Guard that holds [`CONFIG_LOCK`] and resets the global judge
+    /// configurations when dropped, even if the test fails.
+    struct ConfigGuard {
+        _lock: MutexGuard<'static, ()>
+    }
+
+    impl Drop for ConfigGuard {
+        fn drop(&mut self) {
+            crate::report::set_protocol(Protocol::Wave);
+            crate::report::set_feedback_dir(None);
+            crate::report::set_result_file(None);
+            crate::report::set_judge_log_file(None);
+        }
+    }
+
+    fn lock_config() -> ConfigGuard {
+        ConfigGuard { _lock: CONFIG_LOCK.lock().unwrap_or_else(|e| e.into_inner()) }
+    }
+
+    fn write_temp_file(name: &str, content: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(
+            format!("wave_test_lib_{}_{}", std::process::id(), name));
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_checker_from_paths() {
+        let _config = lock_config();
+        let input = write_temp_file("checker_input", "1 2\n");
+        let std_answer = write_temp_file("checker_std_answer", "3\n");
+        let user_answer = write_temp_file("checker_user_answer", "3\n");
+
+        let mut checker = Checker::from_args(
+            vec![PathBuf::from("checker"), input, std_answer, user_answer])
+            .unwrap();
+        assert_eq!(checker.input().read_token_as::<i32>(), Some(1));
+        assert_eq!(checker.input().read_token_as::<i32>(), Some(2));
+        assert_eq!(checker.std_answer().read_token(), Some(String::from("3")));
+        assert_eq!(checker.user_answer().read_token(), Some(String::from("3")));
+    }
+
+    #[test]
+    fn test_checker_from_args_errors() {
+        let _config = lock_config();
+        match Checker::from_args(vec!["checker", "input"]) {
+            Err(InitError::MissingArgument("std_answer")) => (),
+            _ => panic!("expected missing argument error")
+        }
+
+        let input = write_temp_file("checker_error_input", "");
+        let missing = std::env::temp_dir().join("wave_test_lib_missing_file");
+        match Checker::from_paths(&input, &missing, &input) {
+            Err(InitError::Io { path, .. }) => assert_eq!(path, missing),
+            _ => panic!("expected I/O error")
+        }
+
+        match Checker::from_args(vec![
+            PathBuf::from("checker"), input.clone(), input.clone(), input.clone(), 
+            PathBuf::from("extra")]) {
+            Err(InitError::UnexpectedArgument(arg)) => assert_eq!(arg, "extra"),
+            _ => panic!("expected unexpected argument error")
+        }
+    }
+
+    #[test]
+    fn test_pipe_end_from_arg() {
+        assert_eq!(PipeEnd::from_arg("3"), PipeEnd::Fd(3));
+        assert_eq!(PipeEnd::from_arg("./3"), PipeEnd::Path(PathBuf::from("./3")));
+        assert_eq!(PipeEnd::from_arg("/tmp/fifo"), PipeEnd::Path(PathBuf::from("/tmp/fifo")));
+        assert_eq!(PipeEnd::from_arg("-1"), PipeEnd::Path(PathBuf::from("-1")));
+    }
+
+    #[test]
+    fn test_interactor_from_args() {
+        let _config = lock_config();
+        let input = write_temp_file("interactor_input", "5\n");
+        let answer = write_temp_file("interactor_answer", "7\n");
+        let read_end = write_temp_file("interactor_read_end", "guess 7\n");
+        let write_end = write_temp_file("interactor_write_end", "");
+
+        let mut interactor = Interactor::from_args(vec![
+            PathBuf::from("interactor"), input, answer, read_end, write_end.clone()])
+            .unwrap();
+        assert_eq!(interactor.input().read_token(), Some(String::from("5")));
+        assert_eq!(interactor.answer().read_token(), Some(String::from("7")));
+        assert_eq!(interactor.read_end().read_token(), Some(String::from("guess")));
+        interactor.write_end().write_all(b"ok\n").unwrap();
+        drop(interactor);
+
+        assert_eq!(fs::read_to_string(&write_end).unwrap(), "ok\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_interactor_from_fds() {
+        use std::os::unix::io::IntoRawFd;
+
+        let input = write_temp_file("interactor_fd_input", "");
+        let read_end = write_temp_file("interactor_fd_read_end", "1 2 3");
+        let read_fd = fs::File::open(&read_end).unwrap().into_raw_fd();
+        let write_end = write_temp_file("interactor_fd_write_end", "");
+        let write_fd = fs::OpenOptions::new().write(true).open(&write_end)
+            .unwrap().into_raw_fd();
+
+        let mut interactor = Interactor::from_pipes(&input, &input, 
+            PipeEnd::Fd(read_fd), PipeEnd::Fd(write_fd)).unwrap();
+        assert_eq!(interactor.read_end().read_token_as::<i32>(), Some(1));
+        interactor.write_end().write_all(b"4").unwrap();
+        drop(interactor);
+
+        assert_eq!(fs::read_to_string(&write_end).unwrap(), "4");
+    }
+
+    #[test]
+    fn test_interactor_invalid_fd() {
+        let input = write_temp_file("interactor_bad_fd_input", "");
+        let write_end = write_temp_file("interactor_bad_fd_write_end", "");
+
+        match Interactor::from_pipes(&input, &input, 
+            PipeEnd::Fd(1 << 20), PipeEnd::Path(write_end.clone())) {
+            Err(InitError::InvalidDescriptor { fd, .. }) => assert_eq!(fd, 1 << 20),
+            _ => panic!("expected invalid descriptor error")
+        }
+
+        for fd in 0..=2 {
+            match Interactor::from_pipes(&input, &input, 
+                PipeEnd::Fd(fd), PipeEnd::Path(write_end.clone())) {
+                Err(InitError::InvalidDescriptor { fd: bad_fd, .. }) => assert_eq!(bad_fd, fd),
+                _ => panic!("expected invalid descriptor error")
+            }
+        }
+    }
+
+    #[test]
+    fn test_options_extract() {
+        let (args, options) = Options::extract(vec![
+            "checker", "--result-file", "result.xml", "input", "--result-format=xml", 
+            "--judge-log=jury.log", "--", "--answer"]).unwrap();
+        assert_eq!(args.collect::<Vec<_>>(), vec!["input", "--answer"]);
+        assert_eq!(options.result_file, Some(PathBuf::from("result.xml")));
+        assert_eq!(options.result_format, Some(ResultFormat::Xml));
+        assert_eq!(options.judge_log, Some(PathBuf::from("jury.log")));
+
+        match Options::extract(vec!["checker", "--result-format=yaml"]) {
+            Err(InitError::InvalidOption(..)) => (),
+            _ => panic!("expected invalid option error")
+        }
+        match Options::extract(vec!["checker", "--unknown=1"]) {
+            Err(InitError::InvalidOption(..)) => (),
+            _ => panic!("expected invalid option error")
+        }
+        match Options::extract(vec!["checker", "--result-file"]) {
+            Err(InitError::InvalidOption(..)) => (),
+            _ => panic!("expected invalid option error")
+        }
+    }
+
+    #[test]
+    fn test_options_apply() {
+        let _config = lock_config();
+        let (_, options) = Options::extract(vec!["checker", "--result-format", "json"])
+            .unwrap();
+        assert!(options.apply().is_err());
+
+        let (_, options) = Options::extract(vec!["checker", "--result-file", "r.json"])
+            .unwrap();
+        options.apply().unwrap();
+        assert_eq!(crate::report::result_file(), Some(ResultFile {
+            path: PathBuf::from("r.json"),
+            format: ResultFormat::Json
+        }));
+    }
+
+    #[test]
+    fn test_checker_kattis_args() {
+        let _config = lock_config();
+        let input = write_temp_file("kattis_input", "1\n");
+        let std_answer = write_temp_file("kattis_std_answer", "2\n");
+        let user_answer = write_temp_file("kattis_user_answer", "3\n");
+        let feedback_dir = std::env::temp_dir();
+
+        let mut checker = Checker::from_protocol_args_with_stdin(Protocol::Kattis, vec![
+            PathBuf::from("validator"), input.clone(), std_answer.clone(), 
+            feedback_dir.clone(), PathBuf::from("float_tolerance"), PathBuf::from("1e-6")],
+            || Ok(fs::File::open(&user_answer).unwrap()))
+            .unwrap();
+        assert_eq!(checker.extra_args(), &["float_tolerance", "1e-6"]);
+        assert_eq!(checker.std_answer().read_token_as::<i32>(), Some(2));
+        assert_eq!(checker.user_answer().read_token_as::<i32>(), Some(3));
+        assert_eq!(crate::report::feedback_dir(), Some(feedback_dir));
+
+        match Checker::from_protocol_args(Protocol::Kattis, vec![
+            PathBuf::from("validator"), input.clone(), std_answer, input]) {
+            Err(InitError::Io { .. }) => (),
+            _ => panic!("expected feedback directory error")
+        }
+    }
+
+    #[test]
+    fn test_checker_testlib_args() {
+        let _config = lock_config();
+        let input = write_temp_file("testlib_input", "1\n");
+        let output = write_temp_file("testlib_output", "2\n");
+        let answer = write_temp_file("testlib_answer", "3\n");
+        let result = std::env::temp_dir().join("wave_test_lib_testlib_result.xml");
+
+        let mut checker = Checker::from_protocol_args(Protocol::Testlib, vec![
+            PathBuf::from("checker"), input.clone(), output.clone(), answer.clone(), 
+            result.clone(), PathBuf::from("-appes")]).unwrap();
+        assert_eq!(checker.user_answer().read_token_as::<i32>(), Some(2));
+        assert_eq!(checker.std_answer().read_token_as::<i32>(), Some(3));
+        assert_eq!(crate::report::result_file(), Some(ResultFile {
+            path: result,
+            format: ResultFormat::Xml
+        }));
+
+        match Checker::from_protocol_args(Protocol::Testlib, vec![
+            PathBuf::from("checker"), input, output, answer, 
+            PathBuf::from("result.txt"), PathBuf::from("-xml")]) {
+            Err(InitError::UnexpectedArgument(arg)) => assert_eq!(arg, "-xml"),
+            _ => panic!("expected unexpected argument error")
+        }
+    }
+
+    #[test]
+    fn test_validator() {
+        let _config = lock_config();
+        let input = write_temp_file("validator_input", "3 100000\n01 -0\n");
+        let mut validator = Validator::from_args(vec![PathBuf::from("validator"), input])
+            .unwrap();
+
+        assert_eq!(validator.try_read_int_in_range(1, 10, "n"), Ok(3));
+        validator.reader().try_expect_space().unwrap();
+        assert_eq!(validator.try_read_int_in_range(1, 100000, "m"), Ok(100000));
+        validator.reader().try_expect_eoln().unwrap();
+        assert_eq!(validator.coverage().get("n"),
+            Some(&BoundCoverage { min_hit: false, max_hit: false }));
+        assert_eq!(validator.coverage().get("m"),
+            Some(&BoundCoverage { min_hit: false, max_hit: true }));
+
+        let err = validator.try_read_int_in_range(0, 10, "a").unwrap_err();
+        assert_eq!(err.kind, ReadErrorKind::InvalidToken);
+        validator.reader().try_expect_space().unwrap();
+        let err = validator.try_read_int_in_range(0i64, 10, "b").unwrap_err();
+        assert_eq!(err.kind, ReadErrorKind::InvalidToken);
+    }
+
+    #[test]
+    fn test_validator_range() {
+        let input = write_temp_file("validator_range_input", "100001\n");
+        let mut validator = Validator::from_path(input).unwrap();
+
+        let err = validator.try_read_int_in_range(1, 100000, "n").unwrap_err();
+        assert_eq!(err.kind, ReadErrorKind::OutOfRange);
+        assert_eq!(err.to_string(),
+            "n = 100001 violates 1 \u{2264} n \u{2264} 100000 (line 1, column 1, token 1)");
+    }
+
+    #[test]
+    fn test_validator_canonical_int() {
+        let input = write_temp_file("validator_canonical_input", "300 +1 -0 1.0 -5");
+        let mut validator = Validator::from_path(input).unwrap();
+
+        let err = validator.try_read_int_in_range(0u8, 255, "a").unwrap_err();
+        assert_eq!(err.expected.as_deref(), Some("integer overflow"));
+        validator.reader().try_expect_space().unwrap();
+        let err = validator.try_read_int_in_range(0i32, 10, "b").unwrap_err();
+        assert_eq!(err.expected.as_deref(), Some("leading plus sign is not allowed"));
+        validator.reader().try_expect_space().unwrap();
+        let err = validator.try_read_int_in_range(-10i64, 10, "c").unwrap_err();
+        assert_eq!(err.expected.as_deref(), Some("negative zero is not allowed"));
+        validator.reader().try_expect_space().unwrap();
+        let err = validator.try_read_int_in_range(0usize, 10, "d").unwrap_err();
+        assert_eq!(err.kind, ReadErrorKind::InvalidToken);
+        validator.reader().try_expect_space().unwrap();
+        assert_eq!(validator.try_read_int_in_range(-5i16, 5, "e"), Ok(-5));
+    }
+}
@@ -0,0 +1,722 @@
+use std::fmt::Write;
+use std::io::Write as IoWrite;
+use std::path::PathBuf;
+use std::process::exit;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use crate::contract::Verdict;
+
+
+/// Environment variable that specifies the path to the result file.
+pub const ENV_RESULT_FILE: &str = "WAVE_RESULT_FILE";
+
+/// Environment variable that specifies the format of the result file.
+pub const ENV_RESULT_FORMAT: &str = "WAVE_RESULT_FORMAT";
+
+/// Environment variable that specifies the protocol used to communicate with
+/// the judge system.
+pub const ENV_PROTOCOL: &str = "WAVE_PROTOCOL";
+
+/// Environment variable that specifies the path to the judge log file.
+pub const ENV_JUDGE_LOG: &str = "WAVE_JUDGE_LOG";
+
+/// Path to the judge log file used if neither a judge log file nor a result
+/// file is set.
+pub const DEFAULT_JUDGE_LOG: &str = "judge.log";
+
+/// Protocol used to communicate with the judge system, which determines the
+/// layout of the command line arguments and how the final report is delivered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Protocol {
+    /// The native protocol of the Wave judge system. The verdict is reported
+    /// through the exit code and stderr, and the judge log is written to the
+    /// judge log file.
+    Wave,
+
+    /// The output validator protocol of the ICPC problem package format, used
+    /// by Kattis and DOMjudge. The exit code is 42 for accepted and 43 for
+    /// rejected, and messages are written into the feedback directory.
+    Kattis,
+
+    /// The checker protocol of testlib, used by Codeforces and Polygon. The exit
+    /// code is 0 for accepted, 1 for wrong answer, 2 for presentation error, 3
+    /// for judge failure and 7 for partial score.
+    Testlib,
+
+    /// The comparator protocol of CMS (Contest Management System). The score
+    /// is written to stdout and the message to stderr, and the exit code is
+    /// always zero unless the judge fails.
+    Cms
+}
+
+impl Protocol {
+    /// Create a new [`Protocol`] from the [`ENV_PROTOCOL`] environment variable.
+    /// Returns `Ok(None)` if the environment variable is not set.
+    pub fn from_env() -> Result<Option<Protocol>, String> {
+        match std::env::var(ENV_PROTOCOL) {
+            Ok(protocol) if !protocol.is_empty() => protocol.parse().map(Some),
+            _ => Ok(None)
+        }
+    }
+
+    /// Get the exit code of the process that indicates the given verdict under
+    /// this protocol.
+    pub fn exit_code(self, verdict: Verdict) -> i32 {
+        match self {
+            Protocol::Wave => verdict.exit_code(),
+            Protocol::Kattis => match verdict {
+                Verdict::Accepted | Verdict::PartialScore => 42,
+                Verdict::WrongAnswer | Verdict::PresentationError => 43,
+                Verdict::InvalidInput => 43,
+                Verdict::JudgeFailure => 1
+            },
+            Protocol::Testlib => match verdict {
+                Verdict::Accepted => 0,
+                Verdict::WrongAnswer => 1,
+                Verdict::PresentationError => 2,
+                Verdict::JudgeFailure | Verdict::InvalidInput => 3,
+                Verdict::PartialScore => 7
+            },
+            Protocol::Cms => match verdict {
+                Verdict::JudgeFailure | Verdict::InvalidInput => 1,
+                _ => 0
+            }
+        }
+    }
+}
+
+impl FromStr for Protocol {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Protocol, String> {
+        if s.eq_ignore_ascii_case("wave") {
+            Ok(Protocol::Wave)
+        } else if s.eq_ignore_ascii_case("kattis") || s.eq_ignore_ascii_case("icpc") {
+            Ok(Protocol::Kattis)
+        } else if s.eq_ignore_ascii_case("testlib") {
+            Ok(Protocol::Testlib)
+        } else if s.eq_ignore_ascii_case("cms") {
+            Ok(Protocol::Cms)
+        } else {
+            Err(format!("unknown protocol: \"{}\"", s))
+        }
+    }
+}
+
+/// The protocol used to deliver the final report.
+static PROTOCOL: Mutex<Protocol> = Mutex::new(Protocol::Wave);
+
+/// The feedback directory of the [`Protocol::Kattis`] protocol.
+static FEEDBACK_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Set the protocol used to deliver the final report.
+pub fn set_protocol(protocol: Protocol) {
+    *PROTOCOL.lock().unwrap_or_else(|e| e.into_inner()) = protocol;
+}
+
+/// Get the protocol used to deliver the final report.
+pub fn protocol() -> Protocol {
+    *PROTOCOL.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Set the feedback directory where `judgemessage.txt`, `teammessage.txt` and
+/// `score.txt` are written under the [`Protocol::Kattis`] protocol.
+pub fn set_feedback_dir(feedback_dir: Option<PathBuf>) {
+    *FEEDBACK_DIR.lock().unwrap_or_else(|e| e.into_inner()) = feedback_dir;
+}
+
+/// Get the feedback directory of the [`Protocol::Kattis`] protocol.
+pub fn feedback_dir() -> Option<PathBuf> {
+    FEEDBACK_DIR.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Format of the structured result file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResultFormat {
+    /// A JSON object with `verdict`, `exit_code`, `score`, `points`,
+    /// `max_points`, `message` and `diagnostic` fields.
+    Json,
+
+    /// A testlib-compatible XML document, e.g.
+    /// `<result outcome="accepted">message</result>`.
+    Xml,
+
+    /// A testlib-compatible plain text line, e.g. `ok 3 numbers`.
+    Text
+}
+
+impl FromStr for ResultFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<ResultFormat, String> {
+        if s.eq_ignore_ascii_case("json") {
+            Ok(ResultFormat::Json)
+        } else if s.eq_ignore_ascii_case("xml") {
+            Ok(ResultFormat::Xml)
+        } else if s.eq_ignore_ascii_case("text") {
+            Ok(ResultFormat::Text)
+        } else {
+            Err(format!("unknown result format: \"{}\"", s))
+        }
+    }
+}
+
+/// Location and format of the structured result file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResultFile {
+    pub path: PathBuf,
+    pub format: ResultFormat
+}
+
+impl ResultFile {
+    /// Create a new [`ResultFile`] from the [`ENV_RESULT_FILE`] and
+    /// [`ENV_RESULT_FORMAT`] environment variables. Returns `Ok(None)` if no
+    /// result file is specified. The format defaults to [`ResultFormat::Json`].
+    pub fn from_env() -> Result<Option<ResultFile>, String> {
+        let path = match std::env::var_os(ENV_RESULT_FILE) {
+            Some(path) if !path.is_empty() => PathBuf::from(path),
+            _ => return Ok(None)
+        };
+        let format = match std::env::var(ENV_RESULT_FORMAT) {
+            Ok(format) => format.parse()?,
+            Err(..) => ResultFormat::Json
+        };
+        Ok(Some(ResultFile { path, format }))
+    }
+}
+
+/// The result file that the final report will be written to.
+static RESULT_FILE: Mutex<Option<ResultFile>> = Mutex::new(None);
+
+/// Set the result file that the final report will be written to. Pass `None`
+/// to disable the result file.
+pub fn set_result_file(result_file: Option<ResultFile>) {
+    *RESULT_FILE.lock().unwrap_or_else(|e| e.into_inner()) = result_file;
+}
+
+/// Get the result file that the final report will be written to.
+pub fn result_file() -> Option<ResultFile> {
+    RESULT_FILE.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// The judge log file that the judge-only diagnostic message will be written to.
+static JUDGE_LOG_FILE: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Set the judge log file that the judge-only diagnostic message will be written
+/// to under the [`Protocol::Wave`] protocol. Pass `None` to use the default
+/// location, see [`judge_log_path`].
+pub fn set_judge_log_file(judge_log_file: Option<PathBuf>) {
+    *JUDGE_LOG_FILE.lock().unwrap_or_else(|e| e.into_inner()) = judge_log_file;
+}
+
+/// Get the judge log file set by [`set_judge_log_file`].
+pub fn judge_log_file() -> Option<PathBuf> {
+    JUDGE_LOG_FILE.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Get the path that the judge log is written to, which is the given judge log
+/// file if set, or the path of the given result file with a `.log` suffix
+/// appended, or [`DEFAULT_JUDGE_LOG`] in the working directory otherwise.
+pub fn judge_log_path(judge_log_file: Option<PathBuf>, result_file: Option<&ResultFile>) 
+    -> PathBuf {
+    match (judge_log_file, result_file) {
+        (Some(path), _) => path,
+        (None, Some(result_file)) => {
+            let mut path = result_file.path.clone().into_os_string();
+            path.push(".log");
+            PathBuf::from(path)
+        },
+        (None, None) => PathBuf::from(DEFAULT_JUDGE_LOG)
+    }
+}
+
+/// Contestant-visible feedback accumulated before the program exits.
+static FEEDBACK: Mutex<String> = Mutex::new(String::new());
+
+/// Judge-only diagnostic log accumulated before the program exits.
+static JUDGE_LOG: Mutex<String> = Mutex::new(String::new());
+
+/// Append a line to the given sink.
+fn append_line(sink: &Mutex<String>, line: &str) {
+    let mut sink = sink.lock().unwrap_or_else(|e| e.into_inner());
+    sink.push_str(line);
+    sink.push('\n');
+}
+
+/// Take the accumulated content out of the given sink, leaving it empty.
+/// Returns `None` if nothing has been accumulated.
+fn take_sink(sink: &Mutex<String>) -> Option<String> {
+    let mut sink = sink.lock().unwrap_or_else(|e| e.into_inner());
+    if sink.is_empty() {
+        None
+    } else {
+        Some(std::mem::take(&mut *sink))
+    }
+}
+
+/// Append a line to the contestant-visible feedback, which will be placed before
+/// the message of the final report.
+pub fn append_feedback(line: &str) {
+    append_line(&FEEDBACK, line);
+}
+
+/// Append a line to the judge-only diagnostic log, which will be placed before
+/// the diagnostic message of the final report.
+pub fn append_judge_log(line: &str) {
+    append_line(&JUDGE_LOG, line);
+}
+
+/// Prepend the accumulated content of `sink` to `message`.
+fn prepend_sink(sink: &Mutex<String>, message: Option<String>) -> Option<String> {
+    match (take_sink(sink), message) {
+        (Some(accumulated), Some(message)) => Some(accumulated + &message),
+        (Some(accumulated), None) => Some(String::from(accumulated.trim_end())),
+        (None, message) => message
+    }
+}
+
+/// The final report of a judge.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    /// The verdict.
+    pub verdict: Verdict,
+
+    /// The score earned, in the range of `[0, 1]`. `None` if the verdict is
+    /// [`Verdict::JudgeFailure`] or [`Verdict::InvalidInput`].
+    pub score: Option<f64>,
+
+    /// The points earned, if the score is given as points out of `max_points`.
+    pub points: Option<f64>,
+
+    /// The maximum points of the test, if the score is given as points.
+    pub max_points: Option<f64>,
+
+    /// The message that can be shown to contestants.
+    pub message: Option<String>,
+
+    /// The diagnostic message that should be shown to the jury only.
+    pub diagnostic: Option<String>
+}
+
+impl Report {
+    /// Create a new [`Report`]. The score defaults to `1` for an accepted
+    /// verdict and `0` for rejecting verdicts if not given. The message of a
+    /// [`Verdict::JudgeFailure`] verdict is treated as a diagnostic message.
+    pub fn new(verdict: Verdict, score: Option<f64>, message: Option<&str>) -> Report {
+        let message = message.map(String::from);
+        match verdict {
+            Verdict::JudgeFailure => Report {
+                verdict,
+                score: None,
+                points: None,
+                max_points: None,
+                message: None,
+                diagnostic: message
+            },
+            Verdict::InvalidInput => Report {
+                verdict,
+                score: None,
+                points: None,
+                max_points: None,
+                message,
+                diagnostic: None
+            },
+            _ => Report {
+                verdict,
+                score: Some(score.unwrap_or(if verdict == Verdict::Accepted { 1.0 } else { 0.0 })),
+                points: None,
+                max_points: None,
+                message,
+                diagnostic: None
+            }
+        }
+    }
+
+    /// Attach the points earned out of the maximum points of the test to this
+    /// report. The score is not changed.
+    pub fn with_points(mut self, points: f64, max_points: f64) -> Report {
+        self.points = Some(points);
+        self.max_points = Some(max_points);
+        self
+    }
+
+    /// Get the points earned by a [`Verdict::PartialScore`] verdict, which are
+    /// the points if given, or the score otherwise.
+    fn partial_points(&self) -> Option<f64> {
+        match self.verdict {
+            Verdict::PartialScore => self.points.or(self.score),
+            _ => None
+        }
+    }
+
+    /// Move the accumulated feedback and judge log into the message and the
+    /// diagnostic message of this report, respectively.
+    pub fn collect_sinks(&mut self) {
+        self.message = prepend_sink(&FEEDBACK, self.message.take());
+        self.diagnostic = prepend_sink(&JUDGE_LOG, self.diagnostic.take());
+    }
+
+    /// Format the report as a JSON object.
+    pub fn to_json(&self) -> String {
+        fn json_str(s: &Option<String>) -> String {
+            match s {
+                Some(s) => {
+                    let mut escaped = String::from("\"");
+                    for ch in s.chars() {
+                        match ch {
+                            '"' => escaped.push_str("\\\""),
+                            '\\' => escaped.push_str("\\\\"),
+                            '\n' => escaped.push_str("\\n"),
+                            '\r' => escaped.push_str("\\r"),
+                            '\t' => escaped.push_str("\\t"),
+                            c if (c as u32) < 0x20 => {
+                                write!(escaped, "\\u{:04x}", c as u32).unwrap();
+                            },
+                            c => escaped.push(c)
+                        }
+                    }
+                    escaped.push('"');
+                    escaped
+                },
+                None => String::from("null")
+            }
+        }
+
+        fn json_f64(value: Option<f64>) -> String {
+            match value {
+                Some(value) => value.to_string(),
+                None => String::from("null")
+            }
+        }
+
+        format!("{{\"verdict\":\"{}\",\"exit_code\":{},\"score\":{},\"points\":{},\"max_points\":{},\"message\":{},\"diagnostic\":{}}}",
+            verdict_key(self.verdict), self.verdict.exit_code(), json_f64(self.score),
+            json_f64(self.points), json_f64(self.max_points),
+            json_str(&self.message), json_str(&self.diagnostic))
+    }
+
+    /// Format the report as a testlib-compatible plain text line, e.g.
+    /// `wrong answer 1st numbers differ`.
+    pub fn to_text(&self) -> String {
+        let outcome = match self.verdict {
+            Verdict::Accepted => String::from("ok"),
+            Verdict::WrongAnswer => String::from("wrong answer"),
+            Verdict::PresentationError => String::from("wrong output format"),
+            Verdict::PartialScore => format!("points {}", self.partial_points().unwrap_or(0.0)),
+            Verdict::JudgeFailure | Verdict::InvalidInput => String::from("FAIL")
+        };
+        let text = match self.verdict {
+            Verdict::JudgeFailure => &self.diagnostic,
+            _ => &self.message
+        };
+        match text {
+            Some(text) => format!("{} {}", outcome, text),
+            None => outcome
+        }
+    }
+
+    /// Format the report as a testlib-compatible XML document.
+    pub fn to_xml(&self) -> String {
+        let outcome = match self.verdict {
+            Verdict::Accepted => "accepted",
+            Verdict::WrongAnswer => "wrong-answer",
+            Verdict::PresentationError => "presentation-error",
+            Verdict::PartialScore => "points",
+            Verdict::JudgeFailure | Verdict::InvalidInput => "fail"
+        };
+        let points = match self.partial_points() {
+            Some(points) => format!(" points=\"{}\"", points),
+            None => String::new()
+        };
+        let text = match self.verdict {
+            Verdict::JudgeFailure => &self.diagnostic,
+            _ => &self.message
+        };
+
+        let mut escaped = String::new();
+        for ch in text.as_deref().unwrap_or("").chars() {
+            match ch {
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '&' => escaped.push_str("&amp;"),
+                '"' => escaped.push_str("&quot;"),
+                c => escaped.push(c)
+            }
+        }
+
+        format!("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<result outcome=\"{}\"{}>{}</result>\n",
+            outcome, points, escaped)
+    }
+}
+
+/// Get the machine friendly key of the given verdict.
+fn verdict_key(verdict: Verdict) -> &'static str {
+    match verdict {
+        Verdict::Accepted => "accepted",
+        Verdict::WrongAnswer => "wrong_answer",
+        Verdict::PresentationError => "presentation_error",
+        Verdict::PartialScore => "partial_score",
+        Verdict::JudgeFailure => "judge_failure",
+        Verdict::InvalidInput => "invalid_input"
+    }
+}
+
+/// Write the verdict and the contestant-visible message of the report to stderr.
+fn print_summary(report: &Report) {
+    let head = match (report.verdict, report.score, report.points, report.max_points) {
+        (Verdict::PartialScore, _, Some(points), Some(max_points)) => 
+            format!("{} [{}/{}]", report.verdict, points, max_points),
+        (Verdict::PartialScore, Some(score), _, _) => format!("{} [{}]", report.verdict, score),
+        _ => report.verdict.to_string()
+    };
+    let text = match report.verdict {
+        Verdict::JudgeFailure => &report.diagnostic,
+        _ => &report.message
+    };
+    match text {
+        Some(msg) => eprintln!("{}: {}", head, msg),
+        None => eprintln!("{}.", head)
+    };
+}
+
+/// Deliver the report under the [`Protocol::Wave`] protocol. The judge-only
+/// diagnostic message, if any, is written to the judge log file located by
+/// [`judge_log_path`] rather than stderr, which contestants may see.
+fn deliver_wave(report: &Report) -> std::io::Result<()> {
+    print_summary(report);
+
+    if let Some(diagnostic) = &report.diagnostic {
+        let path = judge_log_path(judge_log_file(), result_file().as_ref());
+        std::fs::write(path, format!("{}\n", diagnostic))?;
+    }
+    Ok(())
+}
+
+/// Deliver the report under the [`Protocol::Kattis`] protocol. The contestant
+/// visible message is written to `teammessage.txt`, the verdict along with the
+/// diagnostic message is written to `judgemessage.txt`, and the points, or the
+/// score if not given as points, of a partial score verdict are written to
+/// `score.txt` in the feedback directory.
+fn deliver_kattis(report: &Report) -> std::io::Result<()> {
+    print_summary(report);
+
+    let feedback_dir = match feedback_dir() {
+        Some(feedback_dir) => feedback_dir,
+        None => return Ok(())
+    };
+    if let Some(message) = &report.message {
+        std::fs::write(feedback_dir.join("teammessage.txt"), message)?;
+    }
+
+    let mut judge_message = report.verdict.to_string();
+    if let Some(message) = &report.message {
+        write!(judge_message, ": {}", message).unwrap();
+    }
+    if let Some(diagnostic) = &report.diagnostic {
+        write!(judge_message, "\n{}", diagnostic).unwrap();
+    }
+    judge_message.push('\n');
+    std::fs::write(feedback_dir.join("judgemessage.txt"), judge_message)?;
+
+    if let Some(points) = report.partial_points() {
+        std::fs::write(feedback_dir.join("score.txt"), format!("{}\n", points))?;
+    }
+    Ok(())
+}
+
+/// Deliver the report under the [`Protocol::Testlib`] protocol. The report is
+/// written to stderr in the testlib plain text style.
+fn deliver_testlib(report: &Report) -> std::io::Result<()> {
+    eprintln!("{}", report.to_text());
+    Ok(())
+}
+
+/// Deliver the report under the [`Protocol::Cms`] protocol. The score is written
+/// to stdout. The contestant-visible message is written to stderr, or one of
+/// the message keys `translate:success`, `translate:wrong` and
+/// `translate:partial` is written if there is no message so that CMS shows a
+/// translated message.
+fn deliver_cms(report: &Report) -> std::io::Result<()> {
+    if report.verdict == Verdict::JudgeFailure {
+        print_summary(report);
+        return Ok(());
+    }
+
+    let mut stdout = std::io::stdout();
+    writeln!(stdout, "{}", report.score.unwrap_or(0.0))?;
+    stdout.flush()?;
+
+    match &report.message {
+        Some(message) => eprintln!("{}", message),
+        None => eprintln!("{}", cms_message_key(report))
+    };
+    Ok(())
+}
+
+/// Get the CMS message key of the given report.
+fn cms_message_key(report: &Report) -> &'static str {
+    match (report.verdict, report.score) {
+        (Verdict::Accepted, _) => "translate:success",
+        (Verdict::PartialScore, Some(score)) if score > 0.0 => "translate:partial",
+        _ => "translate:wrong"
+    }
+}
+
+/// Submit the final report to the judge system and exit the program. The
+/// accumulated feedback and judge log are collected into the report first, and
+/// then the report is delivered according to the current [`Protocol`]. If a
+/// result file is set, the whole report is also written to it.
+pub fn submit(mut report: Report) -> ! {
+    report.collect_sinks();
+
+    let protocol = protocol();
+    let delivered = match protocol {
+        Protocol::Wave => deliver_wave(&report),
+        Protocol::Kattis => deliver_kattis(&report),
+        Protocol::Testlib => deliver_testlib(&report),
+        Protocol::Cms => deliver_cms(&report)
+    };
+    if let Err(err) = delivered {
+        eprintln!("Judge Failure: cannot deliver the result: {}", err);
+        exit(protocol.exit_code(Verdict::JudgeFailure));
+    }
+
+    if let Some(result_file) = result_file() {
+        let content = match result_file.format {
+            ResultFormat::Json => report.to_json(),
+            ResultFormat::Xml => report.to_xml(),
+            ResultFormat::Text => report.to_text()
+        };
+        if let Err(err) = std::fs::write(&result_file.path, content) {
+            eprintln!("Judge Failure: cannot write result file \"{}\": {}",
+                result_file.path.display(), err);
+            exit(protocol.exit_code(Verdict::JudgeFailure));
+        }
+    }
+
+    exit(protocol.exit_code(report.verdict))
+}
+
+pub mod preclude {
+    pub use super::{Protocol, ResultFormat, ResultFile, Report};
+    pub use super::{set_protocol, protocol, set_feedback_dir, feedback_dir};
+    pub use super::{set_result_file, result_file};
+    pub use super::{set_judge_log_file, judge_log_file, judge_log_path};
+    pub use super::{append_feedback, append_judge_log};
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::contract::Verdict;
+
+    use std::path::PathBuf;
+
+    use super::{Protocol, Report, ResultFile, ResultFormat, append_feedback, append_judge_log};
+    use super::judge_log_path;
+    use super::cms_message_key;
+
+    #[test]
+    fn test_report_to_json() {
+        assert_eq!(Report::new(Verdict::Accepted, None, None).to_json(),
+            "{\"verdict\":\"accepted\",\"exit_code\":0,\"score\":1,\"points\":null,\"max_points\":null,\"message\":null,\"diagnostic\":null}");
+        assert_eq!(Report::new(Verdict::PartialScore, Some(0.5), Some("a \"b\"\n")).to_json(),
+            "{\"verdict\":\"partial_score\",\"exit_code\":-4,\"score\":0.5,\"points\":null,\"max_points\":null,\"message\":\"a \\\"b\\\"\\n\",\"diagnostic\":null}");
+        assert_eq!(Report::new(Verdict::JudgeFailure, None, Some("bad answer")).to_json(),
+            "{\"verdict\":\"judge_failure\",\"exit_code\":-2,\"score\":null,\"points\":null,\"max_points\":null,\"message\":null,\"diagnostic\":\"bad answer\"}");
+        assert_eq!(Report::new(Verdict::PartialScore, Some(0.5), None).with_points(3.0, 6.0)
+            .to_json(),
+            "{\"verdict\":\"partial_score\",\"exit_code\":-4,\"score\":0.5,\"points\":3,\"max_points\":6,\"message\":null,\"diagnostic\":null}");
+    }
+
+    #[test]
+    fn test_report_to_xml() {
+        assert_eq!(Report::new(Verdict::WrongAnswer, None, Some("1 < 2")).to_xml(),
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<result outcome=\"wrong-answer\">1 &lt; 2</result>\n");
+        assert_eq!(Report::new(Verdict::PartialScore, Some(0.25), None).to_xml(),
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<result outcome=\"points\" points=\"0.25\"></result>\n");
+        assert_eq!(Report::new(Verdict::PartialScore, Some(0.25), None).with_points(5.0, 20.0)
+            .to_xml(),
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<result outcome=\"points\" points=\"5\"></result>\n");
+    }
+
+    #[test]
+    fn test_report_to_text() {
+        assert_eq!(Report::new(Verdict::Accepted, None, Some("3 numbers")).to_text(),
+            "ok 3 numbers");
+        assert_eq!(Report::new(Verdict::PresentationError, None, None).to_text(),
+            "wrong output format");
+        assert_eq!(Report::new(Verdict::PartialScore, Some(0.5), Some("slow")).to_text(),
+            "points 0.5 slow");
+        assert_eq!(Report::new(Verdict::PartialScore, Some(0.5), Some("slow"))
+            .with_points(3.0, 6.0).to_text(),
+            "points 3 slow");
+        assert_eq!(Report::new(Verdict::JudgeFailure, None, Some("bad answer")).to_text(),
+            "FAIL bad answer");
+    }
+
+    #[test]
+    fn test_result_format_from_str() {
+        assert_eq!("json".parse::<ResultFormat>(), Ok(ResultFormat::Json));
+        assert_eq!("XML".parse::<ResultFormat>(), Ok(ResultFormat::Xml));
+        assert!("yaml".parse::<ResultFormat>().is_err());
+    }
+
+    #[test]
+    fn test_report_collect_sinks() {
+        append_feedback("line 1");
+        append_feedback("line 2");
+        append_judge_log("expected 3, found 4");
+
+        let mut report = Report::new(Verdict::WrongAnswer, None, Some("wrong"));
+        report.collect_sinks();
+        assert_eq!(report.message.as_deref(), Some("line 1\nline 2\nwrong"));
+        assert_eq!(report.diagnostic.as_deref(), Some("expected 3, found 4"));
+
+        let mut report = Report::new(Verdict::Accepted, None, None);
+        report.collect_sinks();
+        assert_eq!(report.message, None);
+        assert_eq!(report.diagnostic, None);
+    }
+
+    #[test]
+    fn test_judge_log_path() {
+        let result_file = ResultFile { path: PathBuf::from("out/r.json"), format: ResultFormat::Json };
+        assert_eq!(judge_log_path(Some(PathBuf::from("jury.txt")), Some(&result_file)),
+            PathBuf::from("jury.txt"));
+        assert_eq!(judge_log_path(None, Some(&result_file)), PathBuf::from("out/r.json.log"));
+        assert_eq!(judge_log_path(None, None), PathBuf::from("judge.log"));
+    }
+
+    #[test]
+    fn test_protocol() {
+        assert_eq!("kattis".parse::<Protocol>(), Ok(Protocol::Kattis));
+        assert_eq!("Wave".parse::<Protocol>(), Ok(Protocol::Wave));
+        assert!("unknown".parse::<Protocol>().is_err());
+
+        assert_eq!(Protocol::Kattis.exit_code(Verdict::Accepted), 42);
+        assert_eq!(Protocol::Kattis.exit_code(Verdict::WrongAnswer), 43);
+        assert_eq!(Protocol::Kattis.exit_code(Verdict::PresentationError), 43);
+        assert_eq!(Protocol::Wave.exit_code(Verdict::WrongAnswer), -1);
+        assert_eq!(Protocol::Testlib.exit_code(Verdict::PresentationError), 2);
+        assert_eq!(Protocol::Testlib.exit_code(Verdict::JudgeFailure), 3);
+        assert_eq!(Protocol::Testlib.exit_code(Verdict::InvalidInput), 3);
+        assert_eq!(Protocol::Kattis.exit_code(Verdict::InvalidInput), 43);
+        assert_eq!(Protocol::Testlib.exit_code(Verdict::PartialScore), 7);
+        assert_eq!(Protocol::Cms.exit_code(Verdict::WrongAnswer), 0);
+        assert_eq!(Protocol::Cms.exit_code(Verdict::JudgeFailure), 1);
+    }
+
+    #[test]
+    fn test_cms_message_key() {
+        assert_eq!(cms_message_key(&Report::new(Verdict::Accepted, None, None)), 
+            "translate:success");
+        assert_eq!(cms_message_key(&Report::new(Verdict::PartialScore, Some(0.5), None)), 
+            "translate:partial");
+        assert_eq!(cms_message_key(&Report::new(Verdict::PartialScore, Some(0.0), None)), 
+            "translate:wrong");
+        assert_eq!(cms_message_key(&Report::new(Verdict::PresentationError, None, None)), 
+            "translate:wrong");
+    }
+}
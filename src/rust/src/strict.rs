@@ -0,0 +1,346 @@
+use std::borrow::Cow;
+use std::io::{Read, BufReader};
+
+use crate::tokenized::{advance, PeekRead, Position, Source, TokenError, TokenizedRead};
+
+
+/// The [`StrictReader`] tokenize the content of the underlying reader while
+/// enforcing the exact format of the content, which is useful for validating
+/// test inputs. Unlike [`crate::tokenized::TokenizedReader`], whitespace
+/// characters are never skipped silently: tokens must be separated explicitly
+/// with [`StrictReader::expect_space`] and [`StrictReader::expect_eoln`], and the
+/// content must end exactly as [`StrictReader::expect_eof_exact`] requires.
+/// Tabs, carriage returns, trailing whitespace characters and missing final
+/// newline are all rejected.
+pub struct StrictReader<T: Read> {
+    source: Source<T>,
+
+    /// Position of the next byte to be read. The token index is the number of
+    /// tokens read so far.
+    cursor: Position,
+
+    /// Position of the token or line returned by the most recent read.
+    last: Position
+}
+
+impl<T: Read> StrictReader<T> {
+    /// Create a new [`StrictReader`] instance, wrapping around the given
+    /// [`Read`] instance.
+    pub fn new(source: T) -> StrictReader<T> {
+        StrictReader {
+            source: Source::new(BufReader::new(source)),
+            cursor: Position::default(),
+            last: Position::default()
+        }
+    }
+
+    /// Peek the next byte from the underlying [`Read`] instance without
+    /// consuming it.
+    fn peek_byte(&mut self) -> Option<u8> {
+        self.source.fill().first().copied()
+    }
+
+    /// Consume the given byte, which must be the byte returned by the most
+    /// recent [`StrictReader::peek_byte`].
+    fn bump(&mut self, byte: u8) {
+        self.source.consume(1);
+        advance(&mut self.cursor, byte);
+    }
+
+    /// Decode the given bytes of the token or line returned by the most recent
+    /// read as UTF-8.
+    fn decode(&self, bytes: Vec<u8>) -> Result<String, TokenError> {
+        String::from_utf8(bytes).map_err(|err| 
+            TokenError::invalid_utf8(err.as_bytes(), &err.utf8_error(), self.last))
+    }
+
+    /// Create a new [`TokenError`] at the position of the next byte. The token
+    /// index of the error is the index that the next token would have.
+    fn error<S: Into<String>>(&self, message: S) -> TokenError {
+        let mut position = self.cursor;
+        position.token_index += 1;
+        TokenError::new(message, position)
+    }
+
+    /// Describe the given byte in error messages.
+    fn describe(byte: Option<u8>) -> String {
+        match byte {
+            Some(b' ') => String::from("space"),
+            Some(b'\t') => String::from("tab"),
+            Some(b'\r') => String::from("carriage return"),
+            Some(b'\n') => String::from("end of line"),
+            Some(b) if b.is_ascii_graphic() => format!("\"{}\"", b as char),
+            Some(b) => format!("byte 0x{:02x}", b),
+            None => String::from("EOF")
+        }
+    }
+
+    /// Expect the next byte to be a single space.
+    pub fn expect_space(&mut self) -> Result<(), TokenError> {
+        match self.peek_byte() {
+            Some(b' ') => {
+                self.bump(b' ');
+                Ok(())
+            },
+            b => Err(self.error(format!("expected space, found {}",
+                StrictReader::<T>::describe(b))))
+        }
+    }
+
+    /// Expect the next byte to be a `\n`. Trailing whitespace characters and
+    /// `\r\n` line endings are rejected.
+    pub fn expect_eoln(&mut self) -> Result<(), TokenError> {
+        match self.peek_byte() {
+            Some(b'\n') => {
+                self.bump(b'\n');
+                Ok(())
+            },
+            Some(b' ') | Some(b'\t') => Err(self.error("trailing whitespace")),
+            Some(b'\r') => Err(self.error("CRLF line ending is not allowed")),
+            None => Err(self.error("missing end of line at the end of file")),
+            b => Err(self.error(format!("expected end of line, found {}",
+                StrictReader::<T>::describe(b))))
+        }
+    }
+
+    /// Expect EOF has been hit exactly, i.e. there are no more bytes left, not
+    /// even whitespace characters.
+    pub fn expect_eof_exact(&mut self) -> Result<(), TokenError> {
+        match self.peek_byte() {
+            None => Ok(()),
+            Some(b'\n') => Err(self.error("extra empty line at the end of file")),
+            b => Err(self.error(format!("expected EOF, found {}",
+                StrictReader::<T>::describe(b))))
+        }
+    }
+
+    /// Run the given read without consuming anything. The position of the
+    /// reader is not changed.
+    fn peek_with<R, F>(&mut self, read: F) -> R
+        where F: FnOnce(&mut Self) -> R {
+        let (cursor, last) = (self.cursor, self.last);
+
+        self.source.start_recording();
+        let result = read(self);
+        self.source.unread_recorded();
+
+        self.cursor = cursor;
+        self.last = last;
+        result
+    }
+
+    /// Get the inner [`BufReader`] instance
+    pub fn inner_reader(&self) -> &BufReader<T> {
+        self.source.inner()
+    }
+}
+
+impl<T: Read> TokenizedRead for StrictReader<T> {
+    /// Read one token from the reader. Panics if the reader is not positioned at
+    /// the beginning of a token; use [`TokenizedRead::try_read_token`] to handle
+    /// the violation.
+    fn read_token(&mut self) -> Option<String> {
+        self.try_read_token().unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Read one line from the reader. Panics if the line contains `\r` or is not
+    /// terminated by `\n`; use [`TokenizedRead::try_read_line`] to handle the
+    /// violation.
+    fn read_line(&mut self) -> Option<String> {
+        self.try_read_line().unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Read the raw bytes of one token from the reader. Panics if the reader is
+    /// not positioned at the beginning of a token.
+    fn read_token_bytes_ref(&mut self) -> Option<Cow<'_, [u8]>> {
+        self.read_token_bytes().map(Cow::Owned)
+    }
+
+    fn try_read_token_bytes_ref(&mut self) -> Result<Option<Cow<'_, [u8]>>, TokenError> {
+        self.try_read_token_bytes().map(|token| token.map(Cow::Owned))
+    }
+
+    /// Read the raw bytes of one token from the reader. Panics if the reader is
+    /// not positioned at the beginning of a token.
+    fn read_token_bytes(&mut self) -> Option<Vec<u8>> {
+        self.try_read_token_bytes().unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn try_read_token(&mut self) -> Result<Option<String>, TokenError> {
+        match self.try_read_token_bytes()? {
+            Some(token) => self.decode(token).map(Some),
+            None => Ok(None)
+        }
+    }
+
+    fn try_read_token_bytes(&mut self) -> Result<Option<Vec<u8>>, TokenError> {
+        self.last = self.cursor;
+        self.last.token_index = self.cursor.token_index + 1;
+
+        let mut buffer = Vec::new();
+        loop {
+            match self.peek_byte() {
+                None => break,
+                Some(b' ') | Some(b'\t') | Some(b'\r') | Some(b'\n') => break,
+                Some(b) => {
+                    buffer.push(b);
+                    self.bump(b);
+                }
+            }
+        }
+
+        if buffer.is_empty() {
+            return match self.peek_byte() {
+                None => Ok(None),
+                b => Err(self.error(format!("expected token, found {}",
+                    StrictReader::<T>::describe(b))))
+            };
+        }
+
+        self.cursor.token_index += 1;
+        Ok(Some(buffer))
+    }
+
+    fn try_read_line(&mut self) -> Result<Option<String>, TokenError> {
+        self.last = self.cursor;
+        self.last.token_index = self.cursor.token_index;
+
+        if self.peek_byte().is_none() {
+            return Ok(None);
+        }
+
+        let mut buffer = Vec::new();
+        loop {
+            match self.peek_byte() {
+                Some(b'\n') => {
+                    self.bump(b'\n');
+                    break;
+                },
+                Some(b'\r') => return Err(self.error("CRLF line ending is not allowed")),
+                Some(b) => {
+                    buffer.push(b);
+                    self.bump(b);
+                },
+                None => return Err(self.error("missing end of line at the end of file"))
+            }
+        }
+
+        self.decode(buffer).map(Some)
+    }
+
+    fn position(&self) -> Position {
+        self.last
+    }
+}
+
+impl<T: Read> PeekRead for StrictReader<T> {
+    fn try_peek_token(&mut self) -> Result<Option<String>, TokenError> {
+        self.peek_with(|reader| reader.try_read_token())
+    }
+
+    fn try_peek_line(&mut self) -> Result<Option<String>, TokenError> {
+        self.peek_with(|reader| reader.try_read_line())
+    }
+
+    /// Determine whether the reader is positioned at the beginning of a token.
+    fn has_next_token(&mut self) -> bool {
+        matches!(self.peek_with(|reader| reader.try_read_token_bytes()), Ok(Some(..)))
+    }
+
+    /// Determine whether EOF has been hit exactly. Unlike
+    /// [`PeekRead::seek_eof`] on other readers, whitespace characters are
+    /// not skipped.
+    fn seek_eof(&mut self) -> bool {
+        self.peek_byte().is_none()
+    }
+}
+
+pub mod preclude {
+    pub use super::StrictReader;
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::tokenized::{PeekRead, TokenizedRead};
+
+    use super::StrictReader;
+
+    #[test]
+    fn test_strict_read() {
+        let mut reader = StrictReader::new("3 4\n1 2 3\n".as_bytes());
+
+        assert_eq!(reader.try_read_token(), Ok(Some(String::from("3"))));
+        reader.expect_space().unwrap();
+        assert_eq!(reader.try_read_token(), Ok(Some(String::from("4"))));
+        reader.expect_eoln().unwrap();
+        assert_eq!(reader.try_read_line(), Ok(Some(String::from("1 2 3"))));
+        reader.expect_eof_exact().unwrap();
+        assert_eq!(reader.try_read_token(), Ok(None));
+    }
+
+    #[test]
+    fn test_strict_whitespace_violations() {
+        let mut reader = StrictReader::new("1  2".as_bytes());
+        reader.try_read_token().unwrap();
+        reader.expect_space().unwrap();
+        let err = reader.try_read_token().unwrap_err();
+        assert_eq!(err.message, "expected token, found space");
+        assert_eq!(err.position.column, 3);
+
+        let mut reader = StrictReader::new("1\t2\n".as_bytes());
+        reader.try_read_token().unwrap();
+        assert_eq!(reader.expect_space().unwrap_err().message, "expected space, found tab");
+
+        let mut reader = StrictReader::new("1 \n".as_bytes());
+        reader.try_read_token().unwrap();
+        assert_eq!(reader.expect_eoln().unwrap_err().message, "trailing whitespace");
+
+        let mut reader = StrictReader::new("1\r\n".as_bytes());
+        reader.try_read_token().unwrap();
+        assert_eq!(reader.expect_eoln().unwrap_err().message,
+            "CRLF line ending is not allowed");
+
+        let mut reader = StrictReader::new("1".as_bytes());
+        reader.try_read_token().unwrap();
+        assert_eq!(reader.expect_eoln().unwrap_err().message,
+            "missing end of line at the end of file");
+
+        let mut reader = StrictReader::new("1\n\n".as_bytes());
+        reader.try_read_token().unwrap();
+        reader.expect_eoln().unwrap();
+        assert_eq!(reader.expect_eof_exact().unwrap_err().message,
+            "extra empty line at the end of file");
+    }
+
+    #[test]
+    fn test_strict_peek() {
+        let mut reader = StrictReader::new("3 4\n".as_bytes());
+
+        assert!(reader.has_next_token());
+        assert_eq!(reader.peek_token(), Some(String::from("3")));
+        assert_eq!(reader.peek_line(), Some(String::from("3 4")));
+        assert_eq!(reader.try_read_token(), Ok(Some(String::from("3"))));
+        assert_eq!(reader.position().column, 1);
+
+        assert!(!reader.has_next_token());
+        reader.expect_space().unwrap();
+        assert_eq!(reader.peek_token(), Some(String::from("4")));
+        assert_eq!(reader.try_read_line(), Ok(Some(String::from("4"))));
+        assert!(reader.seek_eof());
+        assert_eq!(reader.peek_token(), None);
+    }
+
+    #[test]
+    fn test_strict_invalid_utf8() {
+        let mut reader = StrictReader::new(b"1 a\xffb\n\xe4\xbd\n".as_ref());
+        reader.try_read_token().unwrap();
+        reader.expect_space().unwrap();
+        let err = reader.try_peek_token().unwrap_err();
+        assert_eq!(err.to_string(), 
+            "invalid UTF-8: byte 0xff at offset 3 (line 1, column 4, token 2)");
+        assert_eq!(reader.try_read_token_bytes(), Ok(Some(b"a\xffb".to_vec())));
+        reader.expect_eoln().unwrap();
+        assert_eq!(reader.try_read_line().unwrap_err().message, "byte 0xe4 at offset 6");
+    }
+}
@@ -1,107 +1,1149 @@
-use std::io::{Read, BufReader, ErrorKind};
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fmt::{Display, Formatter};
+use std::io::{Read, BufRead, BufReader, ErrorKind};
+use std::str::Utf8Error;
 
 
+/// Position of a token within the content of a [`TokenizedRead`] instance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Position {
+    /// Zero-based byte offset of the first byte of the token.
+    pub offset: u64,
+
+    /// One-based line number of the token.
+    pub line: u64,
+
+    /// One-based column number of the token, counted in bytes.
+    pub column: u64,
+
+    /// One-based index of the token among all tokens read. Lines read by
+    /// [`TokenizedRead::read_line`] are not counted.
+    pub token_index: u64
+}
+
+impl Default for Position {
+    fn default() -> Position {
+        Position {
+            offset: 0,
+            line: 1,
+            column: 1,
+            token_index: 0
+        }
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "line {}, column {}, token {}", self.line, self.column, self.token_index)
+    }
+}
+
+/// Advance the given position over the given byte.
+pub(crate) fn advance(position: &mut Position, byte: u8) {
+    position.offset += 1;
+    if byte == b'\n' {
+        position.line += 1;
+        position.column = 1;
+    } else {
+        position.column += 1;
+    }
+}
+
+/// Advance the given position over the given bytes.
+pub(crate) fn advance_all(position: &mut Position, bytes: &[u8]) {
+    for &b in bytes {
+        advance(position, b);
+    }
+}
+
+/// Kind of a [`TokenError`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenErrorKind {
+    /// The content violates the format required by the reader.
+    InvalidFormat,
+
+    /// The content is not valid UTF-8.
+    InvalidUtf8
+}
+
+/// Error raised by a [`TokenizedRead`] instance when its content violates the
+/// format it requires.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenError {
+    /// Kind of the violation.
+    pub kind: TokenErrorKind,
+
+    /// Description of the violation.
+    pub message: String,
+
+    /// Position where the violation occurs.
+    pub position: Position
+}
+
+impl TokenError {
+    /// Create a new [`TokenError`] instance of kind
+    /// [`TokenErrorKind::InvalidFormat`].
+    pub fn new<S: Into<String>>(message: S, position: Position) -> TokenError {
+        TokenError { kind: TokenErrorKind::InvalidFormat, message: message.into(), position }
+    }
+
+    /// Create a new [`TokenError`] instance of kind
+    /// [`TokenErrorKind::InvalidUtf8`], given the bytes of a token or a line
+    /// starting at the given position and the error raised when decoding them.
+    /// The position of the error is the position of the first invalid byte.
+    pub fn invalid_utf8(bytes: &[u8], err: &Utf8Error, start: Position) -> TokenError {
+        let mut position = start;
+        advance_all(&mut position, &bytes[..err.valid_up_to()]);
+        TokenError {
+            kind: TokenErrorKind::InvalidUtf8,
+            message: format!("byte 0x{:02x} at offset {}", bytes[err.valid_up_to()], 
+                position.offset),
+            position
+        }
+    }
+}
+
+impl Display for TokenError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        if self.kind == TokenErrorKind::InvalidUtf8 {
+            f.write_str("invalid UTF-8: ")?;
+        }
+        write!(f, "{} ({})", self.message, self.position)
+    }
+}
+
+impl std::error::Error for TokenError { }
+
+/// Syntax accepted by the numeric reads on [`TokenizedRead`]. The default
+/// format accepts leading zeros but not a leading `+` sign; use
+/// [`NumberFormat::strict`] to accept canonical numbers only.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NumberFormat {
+    /// Whether a leading `+` sign is accepted.
+    pub allow_plus_sign: bool,
+
+    /// Whether leading zeros in the integral part, such as `007` or `00.5`, are
+    /// accepted. `-0` is rejected as well if this is false.
+    pub allow_leading_zeros: bool
+}
+
+impl NumberFormat {
+    /// Create a new [`NumberFormat`] that accepts canonical numbers only.
+    pub fn strict() -> NumberFormat {
+        NumberFormat { allow_plus_sign: false, allow_leading_zeros: false }
+    }
+}
+
+impl Default for NumberFormat {
+    fn default() -> NumberFormat {
+        NumberFormat { allow_plus_sign: false, allow_leading_zeros: true }
+    }
+}
+
+/// Error raised by the numeric reads on [`TokenizedRead`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NumberError {
+    /// The content violates the format required by the reader.
+    Token(TokenError),
+
+    /// The token is not a valid number in the required format.
+    Invalid {
+        /// The token read.
+        token: String,
+
+        /// Description of the violation.
+        reason: &'static str
+    }
+}
+
+impl Display for NumberError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            NumberError::Token(err) => write!(f, "{}", err),
+            NumberError::Invalid { token, reason } => 
+                write!(f, "invalid number \"{}\": {}", token, reason)
+        }
+    }
+}
+
+impl std::error::Error for NumberError { }
+
+/// Split the sign from the given numeric token, checking it against the given
+/// format. Returns whether the number is negative and the rest of the token.
+fn split_sign<'a>(token: &'a [u8], format: &NumberFormat) 
+    -> Result<(bool, &'a [u8]), &'static str> {
+    match token.first() {
+        Some(b'+') if !format.allow_plus_sign => Err("leading plus sign is not allowed"),
+        Some(b'+') => Ok((false, &token[1..])),
+        Some(b'-') => Ok((true, &token[1..])),
+        _ => Ok((false, token))
+    }
+}
+
+/// Check the integral digits of a number against the given format.
+fn check_leading_zeros(negative: bool, digits: &[u8], is_zero: bool, format: &NumberFormat)
+    -> Result<(), &'static str> {
+    if format.allow_leading_zeros {
+        return Ok(());
+    }
+    if digits.len() > 1 && digits[0] == b'0' {
+        return Err("leading zeros are not allowed");
+    }
+    if negative && is_zero {
+        return Err("negative zero is not allowed");
+    }
+    Ok(())
+}
+
+/// Parse the magnitude of an integer from the given decimal digits.
+fn parse_magnitude(digits: &[u8]) -> Result<u64, &'static str> {
+    if digits.is_empty() {
+        return Err("invalid integer");
+    }
+    let mut value = 0u64;
+    for &d in digits {
+        if !d.is_ascii_digit() {
+            return Err("invalid integer");
+        }
+        value = value.checked_mul(10)
+            .and_then(|value| value.checked_add(u64::from(d - b'0')))
+            .ok_or("integer overflow")?;
+    }
+    Ok(value)
+}
+
+/// Parse an `i64` from the given raw bytes in the given format.
+pub fn parse_i64(token: &[u8], format: &NumberFormat) -> Result<i64, &'static str> {
+    let (negative, digits) = split_sign(token, format)?;
+    let magnitude = parse_magnitude(digits)?;
+    check_leading_zeros(negative, digits, magnitude == 0, format)?;
+    if negative {
+        // `i64::MIN` has no positive counterpart, so negate in `i128`.
+        i64::try_from(-i128::from(magnitude)).map_err(|_| "integer overflow")
+    } else {
+        i64::try_from(magnitude).map_err(|_| "integer overflow")
+    }
+}
+
+/// Parse a `u64` from the given raw bytes in the given format.
+pub fn parse_u64(token: &[u8], format: &NumberFormat) -> Result<u64, &'static str> {
+    let (negative, digits) = split_sign(token, format)?;
+    let magnitude = parse_magnitude(digits)?;
+    check_leading_zeros(negative, digits, magnitude == 0, format)?;
+    if negative && magnitude != 0 {
+        return Err("integer overflow");
+    }
+    Ok(magnitude)
+}
+
+/// Parse an `f64` from the given raw bytes in the given format. Only plain
+/// decimal numbers with an optional fraction and exponent are accepted; `inf`,
+/// `nan` and hexadecimal floating point numbers are rejected.
+pub fn parse_f64(token: &[u8], format: &NumberFormat) -> Result<f64, &'static str> {
+    const INVALID: &str = "invalid floating point number";
+    let (negative, rest) = split_sign(token, format)?;
+
+    let mantissa_len = rest.iter().position(|&b| b == b'e' || b == b'E').unwrap_or(rest.len());
+    let (mantissa, exponent) = rest.split_at(mantissa_len);
+    let integral_len = mantissa.iter().position(|&b| b == b'.').unwrap_or(mantissa.len());
+    let integral = &mantissa[..integral_len];
+    let fraction = mantissa.get(integral_len + 1..).unwrap_or(&[]);
+    if integral.len() + fraction.len() == 0 ||
+        !integral.iter().chain(fraction.iter()).all(u8::is_ascii_digit) {
+        return Err(INVALID);
+    }
+    if let Some((_, exponent)) = exponent.split_first() {
+        let digits = match exponent.first() {
+            Some(b'+') | Some(b'-') => &exponent[1..],
+            _ => exponent
+        };
+        if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+            return Err(INVALID);
+        }
+    }
+
+    let is_zero = integral.iter().chain(fraction.iter()).all(|&d| d == b'0');
+    check_leading_zeros(negative, integral, is_zero, format)?;
+
+    // The syntax has been checked, and the token is ASCII.
+    std::str::from_utf8(token).ok()
+        .and_then(|token| token.parse::<f64>().ok())
+        .ok_or(INVALID)
+}
+
 /// Provide the ability to read textual contents token by token. Tokens are
 /// recognized and splitted by whitespace characters (space, `\t`, `\r` and `\n`).
+/// Lookahead is provided by the [`PeekRead`] subtrait, which every reader in
+/// this crate implements.
 pub trait TokenizedRead {
     /// Read one token from the reader. If no more tokens are available, returns
     /// [`None`]. Panics if the raw bytes of the next token cannot be interpreted
-    /// as a valid UTF-8 string.
+    /// as a valid UTF-8 string; use [`TokenizedRead::try_read_token`] to handle
+    /// invalid UTF-8.
     fn read_token(&mut self) -> Option<String>;
 
     /// Read until either EOF or end-of-line is hit. If no more tokens are available,
     /// returns [`None`]. The end-of-line character (`\n` on unix and `\r\n` on
     /// Windows) and any leading whitespace characters will not be present in the 
     /// returned string. Panics if the raw bytes of the next line cannot be 
-    /// interpreted as a valid UTF-8 string.
+    /// interpreted as a valid UTF-8 string; use [`TokenizedRead::try_read_line`]
+    /// to handle invalid UTF-8.
     fn read_line(&mut self) -> Option<String>;
+
+    /// Read one token from the reader, the same as [`TokenizedRead::read_token`]
+    /// except that the token may be borrowed from the internal buffer of the
+    /// reader to avoid allocation. The default implementation returns the owned
+    /// token read by [`TokenizedRead::read_token`].
+    fn read_token_ref(&mut self) -> Option<Cow<'_, str>> {
+        self.read_token().map(Cow::Owned)
+    }
+
+    /// Read one token from the reader, the same as
+    /// [`TokenizedRead::read_token_ref`] except that a violation of the format
+    /// required by the reader is returned as an error instead of a panic.
+    fn try_read_token_ref(&mut self) -> Result<Option<Cow<'_, str>>, TokenError> {
+        self.try_read_token().map(|token| token.map(Cow::Owned))
+    }
+
+    /// Read the raw bytes of one token from the reader. The token may be
+    /// borrowed from the internal buffer of the reader to avoid allocation.
+    /// Unlike [`TokenizedRead::read_token_ref`], the bytes are not required to
+    /// be valid UTF-8.
+    fn read_token_bytes_ref(&mut self) -> Option<Cow<'_, [u8]>> {
+        self.read_token().map(|token| Cow::Owned(token.into_bytes()))
+    }
+
+    /// Read the raw bytes of one token from the reader, the same as
+    /// [`TokenizedRead::read_token_bytes_ref`] except that a violation of the
+    /// format required by the reader is returned as an error instead of a panic.
+    fn try_read_token_bytes_ref(&mut self) -> Result<Option<Cow<'_, [u8]>>, TokenError> {
+        self.try_read_token().map(|token| token.map(|token| Cow::Owned(token.into_bytes())))
+    }
+
+    /// Read the raw bytes of one token from the reader, which are not required
+    /// to be valid UTF-8. Useful for judging output that is not text.
+    fn read_token_bytes(&mut self) -> Option<Vec<u8>> {
+        self.read_token_bytes_ref().map(Cow::into_owned)
+    }
+
+    /// Read the raw bytes of one token from the reader, the same as
+    /// [`TokenizedRead::read_token_bytes`] except that a violation of the format
+    /// required by the reader is returned as an error instead of a panic.
+    fn try_read_token_bytes(&mut self) -> Result<Option<Vec<u8>>, TokenError> {
+        self.try_read_token_bytes_ref().map(|token| token.map(Cow::into_owned))
+    }
+
+    /// Read one token from the reader and parse it as an `i64` in the given
+    /// format directly from its raw bytes. Returns `Ok(None)` on EOF.
+    fn read_i64(&mut self, format: &NumberFormat) -> Result<Option<i64>, NumberError> {
+        read_number(self, format, parse_i64)
+    }
+
+    /// Read one token from the reader and parse it as a `u64` in the given
+    /// format directly from its raw bytes. Returns `Ok(None)` on EOF.
+    fn read_u64(&mut self, format: &NumberFormat) -> Result<Option<u64>, NumberError> {
+        read_number(self, format, parse_u64)
+    }
+
+    /// Read one token from the reader and parse it as an `f64` in the given
+    /// format directly from its raw bytes. Returns `Ok(None)` on EOF. See
+    /// [`parse_f64`] for the syntax accepted.
+    fn read_f64(&mut self, format: &NumberFormat) -> Result<Option<f64>, NumberError> {
+        read_number(self, format, parse_f64)
+    }
+
+    /// Read one token from the reader, the same as [`TokenizedRead::read_token`]
+    /// except that a violation of the format required by the reader is returned
+    /// as an error instead of a panic.
+    fn try_read_token(&mut self) -> Result<Option<String>, TokenError> {
+        Ok(self.read_token())
+    }
+
+    /// Read one line from the reader, the same as [`TokenizedRead::read_line`]
+    /// except that a violation of the format required by the reader is returned
+    /// as an error instead of a panic.
+    fn try_read_line(&mut self) -> Result<Option<String>, TokenError> {
+        Ok(self.read_line())
+    }
+
+    /// Get the position of the token or line returned by the most recent read.
+    /// If the most recent read hit EOF, the position of EOF is returned, and the
+    /// token index is the index that the next token would have. Readers that
+    /// do not track positions return the default position.
+    fn position(&self) -> Position {
+        Position::default()
+    }
+}
+
+/// Lookahead on a [`TokenizedRead`] instance. Peeking a token or a line does not
+/// consume it, so readers of branchy output formats can decide how to parse the
+/// content before reading it.
+///
+/// Lookahead lives in a subtrait rather than on [`TokenizedRead`] itself because
+/// it cannot be built on top of `read_token` and `read_line`: it needs the
+/// reader to hold the peeked bytes and restore its position, which only the
+/// reader itself can do. Keeping it apart lets external readers implement
+/// [`TokenizedRead`] with its two required methods alone. Both readers in this
+/// crate, [`TokenizedReader`] and [`StrictReader`](crate::strict::StrictReader),
+/// implement [`PeekRead`], and [`JudgeReader`](crate::contract::JudgeReader)
+/// exposes the lookahead of any reader that implements it. Generic code that
+/// needs lookahead should be bounded on [`PeekRead`].
+pub trait PeekRead: TokenizedRead {
+    /// Get the token that the next [`TokenizedRead::try_read_token`] would
+    /// return, without consuming it. The position of the reader is not changed,
+    /// and a subsequent [`TokenizedRead::read_line`] still returns the whole
+    /// line containing the token.
+    fn try_peek_token(&mut self) -> Result<Option<String>, TokenError>;
+
+    /// Get the line that the next [`TokenizedRead::try_read_line`] would
+    /// return, without consuming it. The position of the reader is not changed.
+    fn try_peek_line(&mut self) -> Result<Option<String>, TokenError>;
+
+    /// Peek one token from the reader, the same as [`PeekRead::try_peek_token`]
+    /// except that a violation of the format required by the reader causes a
+    /// panic.
+    fn peek_token(&mut self) -> Option<String> {
+        self.try_peek_token().unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Peek one line from the reader, the same as [`PeekRead::try_peek_line`]
+    /// except that a violation of the format required by the reader causes a
+    /// panic.
+    fn peek_line(&mut self) -> Option<String> {
+        self.try_peek_line().unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Determine whether there are more tokens available, without consuming
+    /// anything. A token that violates the format required by the reader is
+    /// still a token.
+    fn has_next_token(&mut self) -> bool {
+        !matches!(self.try_peek_token(), Ok(None))
+    }
+
+    /// Determine whether only whitespace characters are left before EOF,
+    /// without consuming anything.
+    fn seek_eof(&mut self) -> bool {
+        !self.has_next_token()
+    }
+}
+
+/// Read one token from the given reader and parse it with the given function.
+fn read_number<R, U, F>(reader: &mut R, format: &NumberFormat, parse: F) 
+    -> Result<Option<U>, NumberError>
+    where R: TokenizedRead + ?Sized,
+          F: FnOnce(&[u8], &NumberFormat) -> Result<U, &'static str> {
+    match reader.try_read_token_bytes_ref().map_err(NumberError::Token)? {
+        Some(token) => parse(&token, format)
+            .map(Some)
+            .map_err(|reason| NumberError::Invalid {
+                token: String::from_utf8_lossy(&token).into_owned(),
+                reason
+            }),
+        None => Ok(None)
+    }
+}
+
+/// Non-ASCII characters with the Unicode `White_Space` property, encoded in
+/// UTF-8.
+const UNICODE_WHITESPACES: [&[u8]; 19] = [
+    b"\xc2\x85", b"\xc2\xa0", b"\xe1\x9a\x80",
+    b"\xe2\x80\x80", b"\xe2\x80\x81", b"\xe2\x80\x82", b"\xe2\x80\x83",
+    b"\xe2\x80\x84", b"\xe2\x80\x85", b"\xe2\x80\x86", b"\xe2\x80\x87",
+    b"\xe2\x80\x88", b"\xe2\x80\x89", b"\xe2\x80\x8a",
+    b"\xe2\x80\xa8", b"\xe2\x80\xa9", b"\xe2\x80\xaf", b"\xe2\x81\x9f",
+    b"\xe3\x80\x80"
+];
+
+/// Maximum width in bytes of a delimiter.
+const MAX_DELIMITER_WIDTH: usize = 3;
+
+/// Rules for splitting the content of a [`TokenizedReader`] into tokens.
+///
+/// Fields of the content are separated by delimiters. By default, a run of
+/// consecutive delimiters separates two fields as a single delimiter and
+/// empty fields are skipped, which splits the content on whitespace
+/// characters. For comma-separated content, use [`TokenizerConfig::csv`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenizerConfig {
+    /// Set of ASCII bytes that delimit fields.
+    delimiters: [bool; 256],
+
+    /// Whether a run of consecutive delimiters counts as a single delimiter.
+    /// Otherwise every delimiter separates two fields, except that `\r\n`
+    /// counts as a single delimiter.
+    pub collapse_delimiters: bool,
+
+    /// Whether empty fields are tokens. An empty field at EOF, e.g. after a
+    /// trailing delimiter, is never a token. When `collapse_delimiters` is
+    /// set, the only empty field is the one before a leading run of delimiters.
+    pub empty_tokens: bool,
+
+    /// Whether non-ASCII characters with the Unicode `White_Space` property,
+    /// e.g. U+00A0 NO-BREAK SPACE and U+3000 IDEOGRAPHIC SPACE, are delimiters
+    /// as well.
+    pub unicode_whitespace: bool
+}
+
+/// Classification of the bytes at the beginning of a buffer.
+enum Class {
+    /// A delimiter of the given width.
+    Delimiter(usize),
+
+    /// A byte of a token.
+    Token,
+
+    /// A prefix of a delimiter of the given width that is cut off by the end of
+    /// the buffer.
+    Incomplete(usize)
+}
+
+/// Location of the bytes retrieved by [`TokenizedReader::scan_until`].
+#[derive(Clone, Copy)]
+enum Scanned {
+    /// The bytes are the given number of bytes at the beginning of the buffer
+    /// of the source, which are consumed on the next read.
+    Buffer(usize),
+
+    /// The bytes are assembled in the scratch buffer.
+    Scratch
+}
+
+/// Outcome of searching a buffer for the first byte of the given class.
+enum Search {
+    /// The byte searched is found at the given offset, with the given width if
+    /// it is a delimiter.
+    Found(usize, usize),
+
+    /// The byte searched may start at the given offset, but the buffer cuts off
+    /// the delimiter of the given width.
+    Incomplete(usize, usize),
+
+    /// The byte searched is not found in the buffer.
+    NotFound
+}
+
+impl TokenizerConfig {
+    /// Create a new [`TokenizerConfig`] that splits the content on the given
+    /// delimiters, collapsing runs of delimiters and skipping empty fields.
+    /// Panics if any of the delimiters is not ASCII, since splitting on such a
+    /// byte would split UTF-8 encoded characters.
+    pub fn with_delimiters(delimiters: &[u8]) -> TokenizerConfig {
+        assert!(delimiters.is_ascii(), "delimiters must be ASCII bytes");
+        TokenizerConfig {
+            delimiters: byte_set(delimiters),
+            collapse_delimiters: true,
+            empty_tokens: false,
+            unicode_whitespace: false
+        }
+    }
+
+    /// Create a new [`TokenizerConfig`] for comma-separated content: fields are
+    /// separated by single commas or line endings, and empty fields are tokens.
+    pub fn csv() -> TokenizerConfig {
+        TokenizerConfig {
+            collapse_delimiters: false,
+            empty_tokens: true,
+            ..TokenizerConfig::with_delimiters(b",\r\n")
+        }
+    }
+
+    /// Determine whether the given byte is a delimiter.
+    #[inline]
+    pub fn is_delimiter(&self, byte: u8) -> bool {
+        self.delimiters[byte as usize]
+    }
+
+    /// Classify the bytes at the beginning of the given non-empty buffer. If
+    /// `eof` is true, the buffer ends at EOF and a cut off delimiter is a
+    /// token.
+    fn classify(&self, bytes: &[u8], eof: bool) -> Class {
+        if self.delimiters[bytes[0] as usize] {
+            return Class::Delimiter(1);
+        }
+        if !self.unicode_whitespace || bytes[0] < 0x80 {
+            return Class::Token;
+        }
+
+        let mut incomplete = None;
+        for whitespace in UNICODE_WHITESPACES.iter() {
+            let len = bytes.len().min(whitespace.len());
+            if bytes[..len] == whitespace[..len] {
+                if len == whitespace.len() {
+                    return Class::Delimiter(len);
+                }
+                incomplete = Some(whitespace.len());
+            }
+        }
+
+        match incomplete {
+            Some(width) if !eof => Class::Incomplete(width),
+            _ => Class::Token
+        }
+    }
+
+    /// Search the given buffer for the first delimiter.
+    #[inline]
+    fn find_delimiter(&self, buffer: &[u8], eof: bool) -> Search {
+        if !self.unicode_whitespace {
+            return match buffer.iter().position(|&b| self.delimiters[b as usize]) {
+                Some(offset) => Search::Found(offset, 1),
+                None => Search::NotFound
+            };
+        }
+
+        let mut offset = 0;
+        while offset < buffer.len() {
+            match self.classify(&buffer[offset..], eof) {
+                Class::Delimiter(width) => return Search::Found(offset, width),
+                Class::Incomplete(width) => return Search::Incomplete(offset, width),
+                Class::Token => offset += 1
+            }
+        }
+        Search::NotFound
+    }
+
+    /// Search the given buffer for the first byte that is not a delimiter.
+    #[inline]
+    fn find_token(&self, buffer: &[u8], eof: bool) -> Search {
+        if !self.unicode_whitespace {
+            return match buffer.iter().position(|&b| !self.delimiters[b as usize]) {
+                Some(offset) => Search::Found(offset, 0),
+                None => Search::NotFound
+            };
+        }
+
+        let mut offset = 0;
+        while offset < buffer.len() {
+            match self.classify(&buffer[offset..], eof) {
+                Class::Delimiter(width) => offset += width,
+                Class::Incomplete(width) => return Search::Incomplete(offset, width),
+                Class::Token => return Search::Found(offset, 0)
+            }
+        }
+        Search::NotFound
+    }
+}
+
+impl Default for TokenizerConfig {
+    /// Split the content on whitespace characters, i.e. spaces, tabs, `\r` and
+    /// `\n`.
+    fn default() -> TokenizerConfig {
+        TokenizerConfig::with_delimiters(b" \t\r\n")
+    }
 }
 
+/// Rules for splitting the content into lines.
+const LINE_ENDINGS: TokenizerConfig = TokenizerConfig {
+    delimiters: byte_set(b"\r\n"),
+    collapse_delimiters: true,
+    empty_tokens: false,
+    unicode_whitespace: false
+};
+
 /// The [`TokenizedReader`] tokenize the content of the underlying reader.
+///
+/// Tokens are scanned directly over the internal buffer of the underlying
+/// [`BufReader`]. A token that lies entirely within the buffer is returned by
+/// [`TokenizedRead::read_token_ref`] without being copied; only tokens that
+/// cross the boundary of the buffer are assembled in a scratch buffer, which is
+/// reused across reads.
 pub struct TokenizedReader<T: Read> {
-    inner: BufReader<T>
+    source: Source<T>,
+
+    /// Scratch buffer for tokens that cross the boundary of the buffer of
+    /// `source`.
+    scratch: Vec<u8>,
+
+    /// Number of bytes of the buffer of `source` that have been scanned but not
+    /// consumed yet, since the most recent token is borrowed from them.
+    pending: usize,
+
+    /// Position of the next byte to be read. The token index is the number of
+    /// tokens read so far.
+    cursor: Position,
+
+    /// Position of the token or line returned by the most recent read.
+    last: Position,
+
+    /// Rules for splitting the content into tokens.
+    config: TokenizerConfig,
+
+    /// Whether the most recent read is a token, rather than a line.
+    after_token: bool,
+
+    /// Whether the delimiter that terminates the most recent read is `\r`, so
+    /// that a following `\n` is a part of the same delimiter.
+    after_cr: bool
+}
+
+/// Build a lookup table of the given bytes.
+const fn byte_set(bytes: &[u8]) -> [bool; 256] {
+    let mut set = [false; 256];
+    let mut i = 0;
+    while i < bytes.len() {
+        set[bytes[i] as usize] = true;
+        i += 1;
+    }
+    set
+}
+
+/// Fill the internal buffer of the given [`BufReader`] if it is empty, retrying
+/// on interruption, and get the buffered bytes. An empty slice indicates EOF.
+fn fill_buf<T: Read>(inner: &mut BufReader<T>) -> &[u8] {
+    loop {
+        match inner.fill_buf() {
+            Ok(..) => break,
+            Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+            Err(err) => panic!("Unexpected error while reading: {}", err)
+        }
+    }
+    inner.buffer()
+}
+
+/// Source of raw bytes shared by the readers in this crate. Bytes are served
+/// from the internal buffer of a [`BufReader`], preceded by any bytes that have
+/// been pushed back after a lookahead.
+pub(crate) struct Source<T: Read> {
+    inner: BufReader<T>,
+
+    /// Bytes pushed back by [`Source::unread_recorded`], which are served before
+    /// the bytes from `inner`. Bytes before `pushback_pos` have been consumed.
+    pushback: Vec<u8>,
+    pushback_pos: usize,
+
+    /// Bytes consumed since [`Source::start_recording`], if recording.
+    recording: Option<Vec<u8>>
+}
+
+impl<T: Read> Source<T> {
+    pub(crate) fn new(inner: BufReader<T>) -> Source<T> {
+        Source { inner, pushback: Vec::new(), pushback_pos: 0, recording: None }
+    }
+
+    /// Get the bytes available without reading from the underlying [`Read`]
+    /// instance.
+    pub(crate) fn buffer(&self) -> &[u8] {
+        if self.pushback_pos < self.pushback.len() {
+            &self.pushback[self.pushback_pos..]
+        } else {
+            self.inner.buffer()
+        }
+    }
+
+    /// Read from the underlying [`Read`] instance if no bytes are available, and
+    /// get the available bytes. An empty slice indicates EOF.
+    pub(crate) fn fill(&mut self) -> &[u8] {
+        if self.pushback_pos < self.pushback.len() {
+            return &self.pushback[self.pushback_pos..];
+        }
+        fill_buf(&mut self.inner)
+    }
+
+    /// Consume the first `count` available bytes, which must not exceed the
+    /// bytes returned by the most recent [`Source::fill`].
+    pub(crate) fn consume(&mut self, count: usize) {
+        if self.pushback_pos < self.pushback.len() {
+            if let Some(recording) = &mut self.recording {
+                recording.extend_from_slice(
+                    &self.pushback[self.pushback_pos..self.pushback_pos + count]);
+            }
+            self.pushback_pos += count;
+            if self.pushback_pos == self.pushback.len() {
+                self.pushback.clear();
+                self.pushback_pos = 0;
+            }
+        } else {
+            if let Some(recording) = &mut self.recording {
+                recording.extend_from_slice(&self.inner.buffer()[..count]);
+            }
+            self.inner.consume(count);
+        }
+    }
+
+    /// Like [`Source::fill`], but read until at least `count` bytes are
+    /// available unless EOF is hit. The available bytes are moved into the
+    /// pushback buffer if they do not fit in the internal buffer of `inner`.
+    pub(crate) fn fill_at_least(&mut self, count: usize) -> &[u8] {
+        if self.fill().len() < count {
+            self.pushback.drain(..self.pushback_pos);
+            self.pushback_pos = 0;
+            while self.pushback.len() < count {
+                let buffer = fill_buf(&mut self.inner);
+                if buffer.is_empty() {
+                    break;
+                }
+                let len = buffer.len().min(count - self.pushback.len());
+                self.pushback.extend_from_slice(&buffer[..len]);
+                self.inner.consume(len);
+            }
+        }
+        self.buffer()
+    }
+
+    /// Start recording the bytes consumed, so that they can be pushed back by
+    /// [`Source::unread_recorded`].
+    pub(crate) fn start_recording(&mut self) {
+        self.recording = Some(Vec::new());
+    }
+
+    /// Stop recording and push the bytes consumed since
+    /// [`Source::start_recording`] back, so that they are served again.
+    pub(crate) fn unread_recorded(&mut self) {
+        let mut recorded = self.recording.take().unwrap_or_default();
+        recorded.extend_from_slice(&self.pushback[self.pushback_pos..]);
+        self.pushback = recorded;
+        self.pushback_pos = 0;
+    }
+
+    /// Determine whether any pushed back bytes have not been consumed yet.
+    pub(crate) fn has_pushback(&self) -> bool {
+        self.pushback_pos < self.pushback.len()
+    }
+
+    pub(crate) fn inner(&self) -> &BufReader<T> {
+        &self.inner
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut BufReader<T> {
+        &mut self.inner
+    }
 }
 
 impl<T: Read> TokenizedReader<T> {
-    /// Whitespace characters.
-    const WHITESPACES: &'static [u8] = &[b' ', b'\t', b'\r', b'\n'];
+    /// Default size of the internal buffer in bytes.
+    const DEFAULT_CAPACITY: usize = 64 * 1024;
 
     /// Create a new [`TokenizedReader`] instance, wrapping around the given
     /// [`Read`] instance. The size of the internal buffer will be set to a
-    /// default value.
+    /// default value, and the content will be split on whitespace characters.
     pub fn new(source: T) -> TokenizedReader<T> {
+        TokenizedReader::with_capacity(TokenizedReader::<T>::DEFAULT_CAPACITY, source)
+    }
+
+    /// Create a new [`TokenizedReader`] instance, wrapping around the given
+    /// [`Read`] instance, with an internal buffer of the given size in bytes.
+    pub fn with_capacity(capacity: usize, source: T) -> TokenizedReader<T> {
+        TokenizedReader::with_capacity_and_config(capacity, TokenizerConfig::default(), source)
+    }
+
+    /// Create a new [`TokenizedReader`] instance, wrapping around the given
+    /// [`Read`] instance, that splits the content into tokens by the given
+    /// rules.
+    pub fn with_config(config: TokenizerConfig, source: T) -> TokenizedReader<T> {
+        TokenizedReader::with_capacity_and_config(
+            TokenizedReader::<T>::DEFAULT_CAPACITY, config, source)
+    }
+
+    /// Create a new [`TokenizedReader`] instance, wrapping around the given
+    /// [`Read`] instance, with an internal buffer of the given size in bytes,
+    /// that splits the content into tokens by the given rules.
+    pub fn with_capacity_and_config(capacity: usize, config: TokenizerConfig, source: T)
+        -> TokenizedReader<T> {
         TokenizedReader {
-            inner: BufReader::new(source)
+            source: Source::new(BufReader::with_capacity(capacity, source)),
+            scratch: Vec::new(),
+            pending: 0,
+            cursor: Position::default(),
+            last: Position::default(),
+            config,
+            after_token: false,
+            after_cr: false
         }
     }
 
-    /// Read a single byte from the underlying [`Read`] instance.
-    fn read_byte(&mut self) -> Option<u8> {
-        let mut buffer = [0u8];
-        match self.inner.read_exact(&mut buffer) {
-            Ok(..) => Some(buffer[0]),
-            Err(err) => match err.kind() {
-                ErrorKind::UnexpectedEof => None,
-                _ => panic!("Unexpected error while reading: {}", err)
-            }
+    /// Get the rules for splitting the content into tokens.
+    pub fn config(&self) -> &TokenizerConfig {
+        &self.config
+    }
+
+    /// Consume the bytes scanned by the most recent read.
+    fn consume_pending(&mut self) {
+        self.source.consume(self.pending);
+        self.pending = 0;
+    }
+
+    /// Read from the underlying [`Read`] instance until at least `count` bytes
+    /// are available, unless EOF is hit.
+    fn fill_at_least(source: &mut Source<T>, count: usize) -> &[u8] {
+        if count > 1 {
+            source.fill_at_least(count)
+        } else {
+            source.fill()
         }
     }
 
-    /// Read raw bytes from the underlying [`Read`] instance until one of the given
-    /// delimiters or EOF are hit. Returns `Some(())` if at least one bytes are
-    /// retrived into `buffer`; otherwise returns `None`.
-    fn read_until(&mut self, delimiters: &[u8], buffer: &mut Vec<u8>) -> Option<()> {
-        // Skip any leading whitespace characters.
-        let mut ch: u8;
+    /// Skip delimiters until a byte that is not a delimiter or EOF is hit.
+    /// Returns `false` if EOF is hit.
+    fn skip_delimiters(&mut self, lines: bool) -> bool {
+        let config = if lines { &LINE_ENDINGS } else { &self.config };
+
+        let mut count = 0;
         loop {
-            ch = match self.read_byte() {
-                Some(b) => b,
-                None => return None
+            let buffer = TokenizedReader::fill_at_least(&mut self.source, count);
+            if buffer.is_empty() {
+                return false;
+            }
+
+            let eof = buffer.len() < count;
+            let (len, found) = match config.find_token(buffer, eof) {
+                Search::Found(len, _) => (len, true),
+                Search::Incomplete(len, width) => {
+                    count = width;
+                    (len, false)
+                },
+                Search::NotFound => {
+                    count = 0;
+                    (buffer.len(), false)
+                }
             };
-            if !delimiters.contains(&ch) {
+
+            advance_all(&mut self.cursor, &buffer[..len]);
+            self.source.consume(len);
+            if found {
+                return true;
+            }
+        }
+    }
+
+    /// Handle the beginning of a field when empty fields are tokens. Returns
+    /// `Some(true)` if the field is empty, in which case the delimiter that
+    /// terminates it is consumed; returns `None` if EOF is hit.
+    fn start_field(&mut self) -> Option<bool> {
+        if self.after_cr && self.config.is_delimiter(b'\n') 
+            && self.source.fill().first() == Some(&b'\n') {
+            advance(&mut self.cursor, b'\n');
+            self.source.consume(1);
+        }
+
+        let count = if self.config.unicode_whitespace { MAX_DELIMITER_WIDTH } else { 1 };
+        let buffer = TokenizedReader::fill_at_least(&mut self.source, count);
+        if buffer.is_empty() {
+            return None;
+        }
+
+        let width = match self.config.classify(buffer, buffer.len() < count) {
+            Class::Delimiter(width) => width,
+            _ => return Some(false)
+        };
+
+        self.cursor.token_index += 1;
+        self.last = self.cursor;
+        self.after_cr = buffer[0] == b'\r';
+        advance_all(&mut self.cursor, &buffer[..width]);
+        self.source.consume(width);
+        if self.config.collapse_delimiters {
+            self.skip_delimiters(false);
+        }
+        Some(true)
+    }
+
+    /// Scan raw bytes from the underlying [`Read`] instance until a delimiter
+    /// or EOF are hit. Lines are scanned if `lines` is true; otherwise tokens
+    /// are scanned according to the [`TokenizerConfig`] of the reader. Returns
+    /// the location of the bytes scanned if a token or a line is retrieved,
+    /// which can be resolved by [`TokenizedReader::scanned`]; otherwise returns
+    /// `None`. The delimiter that terminates the bytes is consumed. The
+    /// position of the first byte retrieved, or the position of EOF, is
+    /// recorded as the last position.
+    fn scan_until(&mut self, lines: bool) -> Option<Scanned> {
+        self.consume_pending();
+
+        let fields = !lines && self.config.empty_tokens
+            && !(self.config.collapse_delimiters && self.after_token);
+        let found = if fields {
+            match self.start_field() {
+                Some(true) => {
+                    self.after_token = true;
+                    return Some(Scanned::Buffer(0));
+                },
+                Some(false) => true,
+                None => false
+            }
+        } else {
+            self.skip_delimiters(lines)
+        };
+
+        if !found {
+            self.last = self.cursor;
+            if !lines {
+                self.last.token_index += 1;
+            }
+            return None;
+        }
+
+        if !lines {
+            self.cursor.token_index += 1;
+        }
+        self.last = self.cursor;
+        self.after_token = !lines;
+
+        let config = if lines { &LINE_ENDINGS } else { &self.config };
+        self.scratch.clear();
+        let mut count = 0;
+        loop {
+            let buffer = TokenizedReader::fill_at_least(&mut self.source, count);
+            if buffer.is_empty() {
+                self.after_cr = false;
                 break;
             }
+
+            match config.find_delimiter(buffer, buffer.len() < count) {
+                Search::Found(len, width) => {
+                    self.after_cr = buffer[len] == b'\r';
+                    advance_all(&mut self.cursor, &buffer[..len + width]);
+                    if self.scratch.is_empty() {
+                        // The whole token lies within the buffer, so borrow it
+                        // and consume it on the next read.
+                        self.pending = len + width;
+                        return Some(Scanned::Buffer(len));
+                    }
+                    self.scratch.extend_from_slice(&buffer[..len]);
+                    self.source.consume(len + width);
+                    break;
+                },
+                Search::Incomplete(len, width) => {
+                    advance_all(&mut self.cursor, &buffer[..len]);
+                    self.scratch.extend_from_slice(&buffer[..len]);
+                    self.source.consume(len);
+                    count = width;
+                },
+                Search::NotFound => {
+                    let len = buffer.len();
+                    advance_all(&mut self.cursor, buffer);
+                    self.scratch.extend_from_slice(buffer);
+                    self.source.consume(len);
+                    count = 0;
+                }
+            }
         }
 
-        while !delimiters.contains(&ch) {
-            buffer.push(ch);
-            ch = match self.read_byte() {
-                Some(b) => b,
-                None => break
-            };
+        Some(Scanned::Scratch)
+    }
+
+    /// Get the bytes at the given location retrieved by the most recent
+    /// [`TokenizedReader::scan_until`].
+    fn scanned(&self, scanned: Scanned) -> &[u8] {
+        match scanned {
+            Scanned::Buffer(len) => &self.source.buffer()[..len],
+            Scanned::Scratch => &self.scratch
         }
+    }
 
-        Some(())
+    /// Decode the bytes at the given location retrieved by the most recent
+    /// [`TokenizedReader::scan_until`] as UTF-8.
+    fn decode(&self, scanned: Scanned) -> Result<&str, TokenError> {
+        let bytes = self.scanned(scanned);
+        std::str::from_utf8(bytes).map_err(|err| TokenError::invalid_utf8(bytes, &err, self.last))
+    }
+
+    /// Scan like [`TokenizedReader::scan_until`] without consuming anything, and
+    /// decode the bytes scanned. The position of the reader is not changed.
+    fn peek_until(&mut self, lines: bool) -> Result<Option<String>, TokenError> {
+        self.consume_pending();
+        let state = (self.cursor, self.last, self.after_token, self.after_cr);
+
+        self.source.start_recording();
+        let result = match self.scan_until(lines) {
+            Some(scanned) => self.decode(scanned).map(|text| Some(String::from(text))),
+            None => Ok(None)
+        };
+        self.consume_pending();
+        self.source.unread_recorded();
+
+        (self.cursor, self.last, self.after_token, self.after_cr) = state;
+        result
     }
 
     /// Get the inner [`BufReader`] instance
     pub fn inner_reader(&self) -> &BufReader<T> {
-        &self.inner
+        self.source.inner()
     }
 
     /// Get the mutable inner [`BufRead`] instance that can be used to read
-    /// raw bytes directly.
+    /// raw bytes directly. Panics if the reader holds bytes ahead of the inner
+    /// reader, e.g. after [`PeekRead::peek_token`]; see
+    /// [`TokenizedReader::try_inner_reader_mut`].
     pub fn inner_reader_mut(&mut self) -> &mut BufReader<T> {
-        &mut self.inner
+        match self.try_inner_reader_mut() {
+            Some(inner) => inner,
+            None => panic!("the inner reader is behind bytes held by the tokenized reader")
+        }
+    }
+
+    /// Get the mutable inner [`BufRead`] instance that can be used to read
+    /// raw bytes directly. Returns `None` if the reader holds bytes ahead of the
+    /// inner reader, e.g. bytes that have been peeked but not read yet, which
+    /// would be skipped by reading from the inner reader.
+    pub fn try_inner_reader_mut(&mut self) -> Option<&mut BufReader<T>> {
+        self.consume_pending();
+        if self.source.has_pushback() {
+            None
+        } else {
+            Some(self.source.inner_mut())
+        }
     }
 }
 
 impl<T: Read> TokenizedRead for TokenizedReader<T> {
     fn read_token(&mut self) -> Option<String> {
-        let mut buffer = Vec::new();
-        self.read_until(TokenizedReader::<T>::WHITESPACES, &mut buffer)
-            .map(|_| String::from_utf8(buffer).unwrap())
+        self.read_token_ref().map(Cow::into_owned)
     }
 
     fn read_line(&mut self) -> Option<String> {
-        let mut buffer = Vec::new();
-        self.read_until(&[b'\r', b'\n'], &mut buffer)
-            .map(|_| String::from_utf8(buffer).unwrap())
+        self.try_read_line().unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn read_token_ref(&mut self) -> Option<Cow<'_, str>> {
+        self.try_read_token_ref().unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn try_read_token_ref(&mut self) -> Result<Option<Cow<'_, str>>, TokenError> {
+        match self.scan_until(false) {
+            Some(scanned) => self.decode(scanned).map(|token| Some(Cow::Borrowed(token))),
+            None => Ok(None)
+        }
+    }
+
+    fn read_token_bytes_ref(&mut self) -> Option<Cow<'_, [u8]>> {
+        let scanned = self.scan_until(false)?;
+        Some(Cow::Borrowed(self.scanned(scanned)))
+    }
+
+    fn try_read_token_bytes_ref(&mut self) -> Result<Option<Cow<'_, [u8]>>, TokenError> {
+        Ok(self.read_token_bytes_ref())
+    }
+
+    fn try_read_token(&mut self) -> Result<Option<String>, TokenError> {
+        self.try_read_token_ref().map(|token| token.map(Cow::into_owned))
+    }
+
+    fn try_read_line(&mut self) -> Result<Option<String>, TokenError> {
+        match self.scan_until(true) {
+            Some(scanned) => self.decode(scanned).map(|line| Some(String::from(line))),
+            None => Ok(None)
+        }
+    }
+
+    fn position(&self) -> Position {
+        self.last
+    }
+}
+
+impl<T: Read> PeekRead for TokenizedReader<T> {
+    fn try_peek_token(&mut self) -> Result<Option<String>, TokenError> {
+        self.peek_until(false)
+    }
+
+    fn try_peek_line(&mut self) -> Result<Option<String>, TokenError> {
+        self.peek_until(true)
     }
 }
 
 pub mod preclude {
-    pub use super::{TokenizedRead, TokenizedReader};
+    pub use super::{Position, TokenError, TokenErrorKind, TokenizedRead, TokenizedReader};
+    pub use super::{PeekRead, TokenizerConfig};
+    pub use super::{NumberError, NumberFormat, parse_i64, parse_u64, parse_f64};
 }
 
 
@@ -183,4 +1225,318 @@ mod tests {
             Some(String::from("quick dog jumps over the lazy brown fox")));
         assert_eq!(reader.read_line(), None);
     }
+
+    #[test]
+    fn test_position() {
+        let mut reader = TokenizedReader::new("ab  cd\n\n  ef\n".as_bytes());
+
+        assert_eq!(reader.position(), Position::default());
+        reader.read_token();
+        assert_eq!(reader.position(), 
+            Position { offset: 0, line: 1, column: 1, token_index: 1 });
+        reader.read_token();
+        assert_eq!(reader.position(), 
+            Position { offset: 4, line: 1, column: 5, token_index: 2 });
+        reader.read_token();
+        assert_eq!(reader.position(), 
+            Position { offset: 10, line: 3, column: 3, token_index: 3 });
+        assert_eq!(reader.read_token(), None);
+        assert_eq!(reader.position(), 
+            Position { offset: 13, line: 4, column: 1, token_index: 4 });
+        assert_eq!(reader.position().to_string(), "line 4, column 1, token 4");
+    }
+
+    #[test]
+    fn test_read_across_buffer_boundary() {
+        let content = "The quick brown fox jumps over the lazy dog\nThe quick dog jumps over the lazy brown fox\n";
+        let mut expected = create_test_reader();
+        let mut reader = TokenizedReader::with_capacity(4, MemoryRead::new(content.as_bytes()));
+
+        while let Some(token) = expected.read_token() {
+            assert_eq!(reader.read_token_ref().as_deref(), Some(token.as_str()));
+            assert_eq!(reader.position(), expected.position());
+        }
+        assert_eq!(reader.read_token_ref(), None);
+        assert_eq!(reader.position(), expected.position());
+
+        let mut reader = TokenizedReader::with_capacity(3, MemoryRead::new(content.as_bytes()));
+        assert_eq!(reader.read_line(), 
+            Some(String::from("The quick brown fox jumps over the lazy dog")));
+        assert_eq!(reader.read_token_ref().as_deref(), Some("The"));
+        assert_eq!(reader.read_line(), 
+            Some(String::from("quick dog jumps over the lazy brown fox")));
+        assert_eq!(reader.read_line(), None);
+    }
+
+    const GENERATED_LINE: &[u8] = b"1234567 -89012 345678901\n";
+
+    /// Generates `remaining` lines of `line` without holding the whole content
+    /// in memory.
+    struct GeneratedRead {
+        line: &'static [u8],
+        remaining: usize,
+        offset: usize
+    }
+
+    impl Read for GeneratedRead {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let mut written = 0;
+            while written < buf.len() && self.remaining > 0 {
+                let len = (buf.len() - written).min(self.line.len() - self.offset);
+                buf[written..written + len]
+                    .copy_from_slice(&self.line[self.offset..self.offset + len]);
+                written += len;
+                self.offset += len;
+                if self.offset == self.line.len() {
+                    self.offset = 0;
+                    self.remaining -= 1;
+                }
+            }
+            Ok(written)
+        }
+    }
+
+    /// Reads `lines` lines generated by [`GeneratedRead`] through
+    /// [`TokenizedReader::read_token_ref`] and checks the tokens read.
+    fn read_generated(lines: usize) {
+        let line = GENERATED_LINE;
+        let mut reader = TokenizedReader::new(
+            GeneratedRead { line, remaining: lines, offset: 0 });
+
+        let mut tokens = 0usize;
+        let mut bytes = 0usize;
+        while let Some(token) = reader.read_token_ref() {
+            tokens += 1;
+            bytes += token.len();
+        }
+
+        assert_eq!(tokens, lines * 3);
+        assert_eq!(bytes, lines * (line.len() - 3));
+        assert_eq!(reader.position().line, lines as u64 + 1);
+        assert_eq!(reader.position().offset, (lines * line.len()) as u64);
+    }
+
+    #[test]
+    fn test_read_generated() {
+        read_generated(10000);
+    }
+
+    /// Reads a generated input of about 256 MiB and checks that the throughput
+    /// is at least 20 MiB/s, far below the measured throughput so that slow
+    /// machines pass. Run with `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore]
+    fn test_read_token_ref_throughput() {
+        const MIN_THROUGHPUT: f64 = 20.0;
+        let lines = (256 << 20) / GENERATED_LINE.len();
+
+        let start = std::time::Instant::now();
+        read_generated(lines);
+        let size = lines * GENERATED_LINE.len();
+        let throughput = size as f64 / (1 << 20) as f64 / start.elapsed().as_secs_f64();
+        assert!(throughput >= MIN_THROUGHPUT, 
+            "read at {:.1} MiB/s, expected at least {:.1} MiB/s", throughput, MIN_THROUGHPUT);
+    }
+
+    #[test]
+    fn test_parse_integers() {
+        let lenient = NumberFormat::default();
+        let strict = NumberFormat::strict();
+        assert_eq!(parse_i64(b"-9223372036854775808", &strict), Ok(i64::MIN));
+        assert_eq!(parse_i64(b"9223372036854775807", &strict), Ok(i64::MAX));
+        assert_eq!(parse_i64(b"9223372036854775808", &strict), Err("integer overflow"));
+        assert_eq!(parse_i64(b"99999999999999999999", &strict), Err("integer overflow"));
+        assert_eq!(parse_i64(b"007", &lenient), Ok(7));
+        assert_eq!(parse_i64(b"007", &strict), Err("leading zeros are not allowed"));
+        assert_eq!(parse_i64(b"-0", &lenient), Ok(0));
+        assert_eq!(parse_i64(b"-0", &strict), Err("negative zero is not allowed"));
+        assert_eq!(parse_i64(b"+1", &lenient), Err("leading plus sign is not allowed"));
+        assert_eq!(parse_i64(b"+1", &NumberFormat { allow_plus_sign: true, ..strict }), Ok(1));
+        assert_eq!(parse_i64(b"-", &lenient), Err("invalid integer"));
+        assert_eq!(parse_i64(b"1a", &lenient), Err("invalid integer"));
+
+        assert_eq!(parse_u64(b"18446744073709551615", &strict), Ok(u64::MAX));
+        assert_eq!(parse_u64(b"18446744073709551616", &strict), Err("integer overflow"));
+        assert_eq!(parse_u64(b"-1", &lenient), Err("integer overflow"));
+    }
+
+    #[test]
+    fn test_parse_f64() {
+        let lenient = NumberFormat::default();
+        let strict = NumberFormat::strict();
+        assert_eq!(parse_f64(b"-1.5e3", &strict), Ok(-1500.0));
+        assert_eq!(parse_f64(b".5", &strict), Ok(0.5));
+        assert_eq!(parse_f64(b"0.25", &strict), Ok(0.25));
+        assert_eq!(parse_f64(b"00.5", &lenient), Ok(0.5));
+        assert_eq!(parse_f64(b"00.5", &strict), Err("leading zeros are not allowed"));
+        assert_eq!(parse_f64(b"-0.0", &strict), Err("negative zero is not allowed"));
+        assert!(parse_f64(b"inf", &lenient).is_err());
+        assert!(parse_f64(b"nan", &lenient).is_err());
+        assert!(parse_f64(b"0x1p3", &lenient).is_err());
+        assert!(parse_f64(b"1e", &lenient).is_err());
+        assert!(parse_f64(b".", &lenient).is_err());
+    }
+
+    #[test]
+    fn test_read_numbers() {
+        let mut reader = TokenizedReader::new("-42 18446744073709551615 2.5 +1".as_bytes());
+        let format = NumberFormat::default();
+        assert_eq!(reader.read_i64(&format), Ok(Some(-42)));
+        assert_eq!(reader.read_u64(&format), Ok(Some(u64::MAX)));
+        assert_eq!(reader.read_f64(&format), Ok(Some(2.5)));
+        assert_eq!(reader.read_i64(&format), Err(NumberError::Invalid {
+            token: String::from("+1"),
+            reason: "leading plus sign is not allowed"
+        }));
+        assert_eq!(reader.read_i64(&format), Ok(None));
+    }
+
+    #[test]
+    fn test_peek() {
+        let mut reader = TokenizedReader::with_capacity(4, 
+            MemoryRead::new(" -1 2 3\n\n  next line\n  ".as_bytes()));
+
+        assert_eq!(reader.peek_token(), Some(String::from("-1")));
+        assert_eq!(reader.peek_token(), Some(String::from("-1")));
+        assert_eq!(reader.position(), Position::default());
+        assert_eq!(reader.peek_line(), Some(String::from(" -1 2 3")));
+        assert_eq!(reader.read_line(), Some(String::from(" -1 2 3")));
+
+        assert!(reader.has_next_token());
+        assert_eq!(reader.peek_token(), Some(String::from("next")));
+        assert_eq!(reader.read_token_ref().as_deref(), Some("next"));
+        assert_eq!(reader.position(), 
+            Position { offset: 11, line: 3, column: 3, token_index: 1 });
+        assert_eq!(reader.peek_line(), Some(String::from("line")));
+        assert_eq!(reader.peek_token(), Some(String::from("line")));
+        assert_eq!(reader.read_line(), Some(String::from("line")));
+
+        assert!(reader.seek_eof());
+        assert_eq!(reader.peek_token(), None);
+        assert_eq!(reader.read_token(), None);
+        assert_eq!(reader.position(), 
+            Position { offset: 23, line: 4, column: 3, token_index: 2 });
+    }
+
+    #[test]
+    fn test_inner_reader_after_peek() {
+        use std::io::BufRead;
+
+        let mut reader = TokenizedReader::new(MemoryRead::new("1 2\n3\n".as_bytes()));
+        assert_eq!(reader.peek_token(), Some(String::from("1")));
+        assert!(reader.try_inner_reader_mut().is_none());
+
+        assert_eq!(reader.read_token(), Some(String::from("1")));
+        let mut rest = String::new();
+        reader.try_inner_reader_mut().unwrap().read_line(&mut rest).unwrap();
+        assert_eq!(rest, "2\n");
+        assert_eq!(reader.read_token(), Some(String::from("3")));
+    }
+
+    fn read_all_tokens<R: Read>(reader: &mut TokenizedReader<R>) -> Vec<String> {
+        std::iter::from_fn(|| reader.read_token()).collect()
+    }
+
+    #[test]
+    fn test_csv() {
+        for &capacity in &[1, 2, 64] {
+            let mut reader = TokenizedReader::with_capacity_and_config(capacity, 
+                TokenizerConfig::csv(), MemoryRead::new("1,2,,3\r\n,x\n\ny,\n".as_bytes()));
+            assert_eq!(read_all_tokens(&mut reader), 
+                vec!["1", "2", "", "3", "", "x", "", "y", ""]);
+            assert_eq!(reader.position(), 
+                Position { offset: 15, line: 5, column: 1, token_index: 10 });
+        }
+
+        let mut reader = TokenizedReader::with_config(TokenizerConfig::csv(), 
+            MemoryRead::new("name,score\nalice,,3\n".as_bytes()));
+        assert_eq!(reader.read_line(), Some(String::from("name,score")));
+        assert_eq!(reader.read_token(), Some(String::from("alice")));
+        assert_eq!(reader.peek_token(), Some(String::from("")));
+        assert_eq!(reader.read_token(), Some(String::from("")));
+        assert_eq!(reader.position(), 
+            Position { offset: 17, line: 2, column: 7, token_index: 2 });
+        assert_eq!(reader.read_token(), Some(String::from("3")));
+        assert_eq!(reader.read_token(), None);
+    }
+
+    #[test]
+    fn test_custom_delimiters() {
+        let config = TokenizerConfig::with_delimiters(b" ,\t\r\n\x0b\x0c");
+        let mut reader = TokenizedReader::with_config(config.clone(), 
+            MemoryRead::new("1, 2,3\x0c\x0b4\n".as_bytes()));
+        assert_eq!(read_all_tokens(&mut reader), vec!["1", "2", "3", "4"]);
+
+        let config = TokenizerConfig { empty_tokens: true, ..config };
+        let mut reader = TokenizedReader::with_config(config, 
+            MemoryRead::new(" , 1,, 2 ".as_bytes()));
+        assert_eq!(read_all_tokens(&mut reader), vec!["", "1", "2"]);
+
+        let config = TokenizerConfig { collapse_delimiters: false, ..TokenizerConfig::csv() };
+        let mut reader = TokenizedReader::with_config(config, 
+            MemoryRead::new("1,,2".as_bytes()));
+        assert_eq!(read_all_tokens(&mut reader), vec!["1", "", "2"]);
+    }
+
+    #[test]
+    fn test_unicode_whitespace() {
+        let config = TokenizerConfig { unicode_whitespace: true, ..TokenizerConfig::default() };
+        for &capacity in &[1, 2, 3, 64] {
+            let mut reader = TokenizedReader::with_capacity_and_config(capacity, config.clone(), 
+                MemoryRead::new("1\u{a0}2\u{3000}\u{2003}€\u{e2}\n\u{205f}".as_bytes()));
+            assert_eq!(read_all_tokens(&mut reader), vec!["1", "2", "€\u{e2}"]);
+        }
+
+        let mut reader = TokenizedReader::new(MemoryRead::new("1\u{a0}2".as_bytes()));
+        assert_eq!(read_all_tokens(&mut reader), vec!["1\u{a0}2"]);
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        for &capacity in &[1, 64] {
+            let mut reader = TokenizedReader::with_capacity(capacity, 
+                MemoryRead::new(b"ok a\xffb\n\xe4\xbd 12\n".as_ref()));
+            assert_eq!(reader.try_read_token(), Ok(Some(String::from("ok"))));
+
+            let err = reader.try_peek_token().unwrap_err();
+            assert_eq!(err.kind, TokenErrorKind::InvalidUtf8);
+            assert_eq!(err.to_string(), 
+                "invalid UTF-8: byte 0xff at offset 4 (line 1, column 5, token 2)");
+            assert!(reader.has_next_token());
+
+            let err = reader.try_read_token().unwrap_err();
+            assert_eq!(err.position, Position { offset: 4, line: 1, column: 5, token_index: 2 });
+            assert_eq!(reader.try_read_line().unwrap_err().message, "byte 0xe4 at offset 7");
+            assert_eq!(reader.read_token_bytes(), None);
+        }
+
+        let mut reader = TokenizedReader::new(MemoryRead::new(b"\xfe\x00 \xc3\xa9".as_ref()));
+        assert_eq!(reader.read_token_bytes(), Some(b"\xfe\x00".to_vec()));
+        assert_eq!(reader.read_token_bytes(), Some("\u{e9}".as_bytes().to_vec()));
+        assert_eq!(reader.read_token_bytes(), None);
+    }
+
+    /// Reader that implements the required methods of [`TokenizedRead`] only.
+    struct MinimalRead {
+        tokens: Vec<String>
+    }
+
+    impl TokenizedRead for MinimalRead {
+        fn read_token(&mut self) -> Option<String> {
+            self.tokens.pop()
+        }
+
+        fn read_line(&mut self) -> Option<String> {
+            self.tokens.pop()
+        }
+    }
+
+    #[test]
+    fn test_minimal_read() {
+        let mut reader = MinimalRead { tokens: vec![String::from("2"), String::from("1")] };
+        assert_eq!(reader.try_read_token(), Ok(Some(String::from("1"))));
+        assert_eq!(reader.read_i64(&NumberFormat::default()), Ok(Some(2)));
+        assert_eq!(reader.read_token_bytes(), None);
+        assert_eq!(reader.position(), Position::default());
+    }
 }